@@ -75,7 +75,7 @@ fn main() {
     // environment variable. By default it's 1, and it must be set to a higher value in order for 
     // the asynchronous runtime to work. The `n_threads` argument indicates how many of these 
     // threads can be used to offload function calls to, and must be lower than the number of 
-    // threads Julia has available to it.
+    // threads Julia has available to it, otherwise initializing the runtime fails.
     //
     // In order to protect the data we get from Julia from being freed by the garbage collector a 
     // stack is maintained. If the stack is too small, jlrs will eventually return an error to
@@ -18,7 +18,7 @@ fn find_julia() -> Option<String> {
 }
 
 #[cfg(target_os = "windows")]
-fn flags() -> Vec<String> {
+fn flags(link: bool) -> Vec<String> {
     let julia_dir = env::var("JULIA_DIR").expect("Julia cannot be found. You can specify the Julia installation path with the JULIA_DIR environment variable.");
     let cygwin_path = env::var("CYGWIN_DIR").expect("Cygwin cannot be found. You can specify the Cygwin installation path with the CYGWIN_DIR environment variable.");
 
@@ -27,8 +27,11 @@ fn flags() -> Vec<String> {
     let w32api_include_path = format!("-I{}/usr/include/w32api", cygwin_path);
     let jl_lib_path = format!("-L{}/bin/", julia_dir);
 
-    println!("cargo:rustc-flags={}", &jl_lib_path);
-    println!("cargo:rustc-link-lib=julia");
+    if link {
+        println!("cargo:rustc-flags={}", &jl_lib_path);
+        println!("cargo:rustc-link-lib=julia");
+    }
+
     vec![
         jl_include_path,
         cygwin_include_path,
@@ -38,22 +41,264 @@ fn flags() -> Vec<String> {
 }
 
 #[cfg(target_os = "linux")]
-fn flags() -> Vec<String> {
+fn flags(link: bool) -> Vec<String> {
     let flags = match find_julia() {
         Some(julia_dir) => {
             let jl_include_path = format!("-I{}/include/julia/", julia_dir);
             let jl_lib_path = format!("-L{}/lib/", julia_dir);
 
-            println!("cargo:rustc-flags={}", &jl_lib_path);
+            if link {
+                println!("cargo:rustc-flags={}", &jl_lib_path);
+            }
+
             vec![jl_include_path]
         }
         None => Vec::new(),
     };
 
-    println!("cargo:rustc-link-lib=julia");
+    if link {
+        println!("cargo:rustc-link-lib=julia");
+    }
+
     flags
 }
 
+// With the `dynamic` feature enabled the bindings can be generated without the Julia headers,
+// in that case the dummy bindings are used.
+#[cfg(target_os = "linux")]
+fn headers_available() -> bool {
+    find_julia().is_some()
+}
+
+#[cfg(target_os = "windows")]
+fn headers_available() -> bool {
+    env::var("JULIA_DIR").is_ok() && env::var("CYGWIN_DIR").is_ok()
+}
+
+// When libjulia is loaded at runtime, every function in an `extern "C"` block is replaced with a
+// function that calls the function pointer resolved with `dlsym`. Every global is replaced with
+// a static that is updated after Julia has been initialized.
+fn dynamic_bindings(bindings: &str) -> String {
+    let mut out = String::with_capacity(bindings.len() * 2);
+    let mut functions = Vec::new();
+    let mut globals = Vec::new();
+    let mut rest = bindings;
+
+    while let Some(start) = rest.find("extern \"C\" {") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + "extern \"C\" {".len()..];
+        let end = rest.find('}').expect("Unterminated extern block");
+        let block = &rest[..end];
+        rest = &rest[end + 1..];
+
+        for item in block.split(';') {
+            let mut item = item.trim();
+            while item.starts_with("#[") {
+                let end = item.find(']').expect("Invalid attribute");
+                item = item[end + 1..].trim();
+            }
+
+            if item.is_empty() {
+                continue;
+            }
+
+            let item = item.split_whitespace().collect::<Vec<_>>().join(" ");
+            if let Some(item) = item.strip_prefix("pub fn ") {
+                functions.push(DynamicFunction::parse(item));
+            } else if let Some(item) = item.strip_prefix("pub static mut ") {
+                let mut parts = item.splitn(2, ':');
+                let name = parts.next().unwrap().trim().to_string();
+                let ty = parts.next().expect("Invalid global").trim().to_string();
+                globals.push((name, ty));
+            } else {
+                panic!("Unsupported item in extern block: {}", item);
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out.push('\n');
+
+    for func in functions.iter() {
+        out.push_str(&func.wrapper());
+    }
+
+    for (name, ty) in globals.iter() {
+        let init = if ty.starts_with("*mut") {
+            "::std::ptr::null_mut()"
+        } else if ty.starts_with("*const") {
+            "::std::ptr::null()"
+        } else {
+            panic!("Unsupported global: {}: {}", name, ty)
+        };
+
+        out.push_str(&format!("pub static mut {}: {} = {};\n", name, ty, init));
+    }
+
+    out.push_str("\n#[allow(non_snake_case)]\n#[doc(hidden)]\npub struct Symbols {\n");
+    for func in functions.iter() {
+        out.push_str(&format!("    {}: {},\n", func.name, func.pointer_type()));
+    }
+    for (name, _) in globals.iter() {
+        out.push_str(&format!("    {}: usize,\n", name));
+    }
+    out.push_str("}\n\n");
+
+    out.push_str(
+        "#[allow(non_snake_case)]\n\
+        unsafe fn load_symbols(lib: &::libloading::Library) -> Result<Symbols, Vec<&'static str>> {\n\
+        \x20   let mut missing = Vec::new();\n",
+    );
+    for func in functions.iter() {
+        out.push_str(&format!(
+            "    let sym_{name} = lib.get::<{ty}>(b\"{name}\\0\").map(|s| *s).ok();\n\
+            \x20   if sym_{name}.is_none() {{ missing.push(\"{name}\"); }}\n",
+            name = func.name,
+            ty = func.pointer_type()
+        ));
+    }
+    for (name, _) in globals.iter() {
+        out.push_str(&format!(
+            "    let sym_{name} = lib.get::<*mut ::std::ffi::c_void>(b\"{name}\\0\").map(|s| *s as usize).ok();\n\
+            \x20   if sym_{name}.is_none() {{ missing.push(\"{name}\"); }}\n",
+            name = name
+        ));
+    }
+    out.push_str(
+        "    if !missing.is_empty() {\n        return Err(missing);\n    }\n\n    Ok(Symbols {\n",
+    );
+    for func in functions.iter() {
+        out.push_str(&format!(
+            "        {name}: sym_{name}.unwrap(),\n",
+            name = func.name
+        ));
+    }
+    for (name, _) in globals.iter() {
+        out.push_str(&format!(
+            "        {name}: sym_{name}.unwrap(),\n",
+            name = name
+        ));
+    }
+    out.push_str("    })\n}\n\n");
+
+    out.push_str("unsafe fn update_globals(symbols: &Symbols) {\n");
+    for (name, ty) in globals.iter() {
+        out.push_str(&format!(
+            "    {name} = *(symbols.{name} as *const {ty});\n",
+            name = name,
+            ty = ty
+        ));
+    }
+    out.push_str("}\n");
+
+    out
+}
+
+struct DynamicFunction {
+    name: String,
+    args: Vec<(String, String)>,
+    ret: String,
+}
+
+impl DynamicFunction {
+    fn parse(item: &str) -> Self {
+        let open = item.find('(').expect("Invalid function");
+        let name = item[..open].trim().to_string();
+
+        let mut depth = 0;
+        let mut close = open;
+        for (idx, c) in item.char_indices().skip_while(|(idx, _)| *idx < open) {
+            match c {
+                '(' | '<' | '[' => depth += 1,
+                ')' | '>' | ']' if !item[..idx].ends_with('-') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = idx;
+                        break;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut args = Vec::new();
+        let mut depth = 0;
+        let mut current = String::new();
+        let arg_list = &item[open + 1..close];
+        for (idx, c) in arg_list.char_indices() {
+            match c {
+                '(' | '<' | '[' => depth += 1,
+                ')' | '>' | ']' if !arg_list[..idx].ends_with('-') => depth -= 1,
+                ',' if depth == 0 => {
+                    args.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                }
+                _ => (),
+            }
+
+            current.push(c);
+        }
+        args.push(current.trim().to_string());
+
+        let args = args
+            .into_iter()
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| {
+                assert!(arg != "...", "Variadic function {} is not supported", name);
+                let mut parts = arg.splitn(2, ':');
+                let arg_name = parts.next().unwrap().trim().to_string();
+                let ty = parts.next().expect("Invalid argument").trim().to_string();
+                (arg_name, ty)
+            })
+            .collect();
+
+        let ret = item[close + 1..].trim().to_string();
+        DynamicFunction { name, args, ret }
+    }
+
+    fn pointer_type(&self) -> String {
+        let types: Vec<&str> = self.args.iter().map(|(_, ty)| ty.as_str()).collect();
+        format!("unsafe extern \"C\" fn({}) {}", types.join(", "), self.ret)
+            .trim()
+            .to_string()
+    }
+
+    fn wrapper(&self) -> String {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|(name, ty)| format!("{}: {}", name, ty))
+            .collect();
+        let names: Vec<&str> = self.args.iter().map(|(name, _)| name.as_str()).collect();
+
+        // Julia's globals are initialized by `jl_init`, so they must be updated afterwards.
+        let body = if self.name.starts_with("jl_init") {
+            format!(
+                "    let symbols = crate::dynamic::symbols();\n\
+                \x20   (symbols.{name})({names});\n\
+                \x20   update_globals(symbols)\n",
+                name = self.name,
+                names = names.join(", ")
+            )
+        } else {
+            format!(
+                "    (crate::dynamic::symbols().{name})({names})\n",
+                name = self.name,
+                names = names.join(", ")
+            )
+        };
+
+        let signature = format!(
+            "pub unsafe fn {}({}) {}",
+            self.name,
+            args.join(", "),
+            self.ret
+        );
+        format!("{} {{\n{}}}\n", signature.trim(), body)
+    }
+}
+
 fn main() {
     let mut out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     out_path.push("bindings.rs");
@@ -62,13 +307,26 @@ fn main() {
     println!("cargo:rerun-if-env-changed=JULIA_DIR");
     println!("cargo:rerun-if-env-changed=CYGWIN_DIR");
 
-    if env::var("CARGO_FEATURE_DOCS_RS").is_ok() {
-        fs::copy("dummy-bindings.rs", &out_path)
+    let dynamic = env::var("CARGO_FEATURE_DYNAMIC").is_ok();
+
+    if env::var("CARGO_FEATURE_DOCS_RS").is_ok() || (dynamic && !headers_available()) {
+        if !dynamic {
+            fs::copy("dummy-bindings.rs", &out_path)
+                .expect("Couldn't create bindings from dummy bindings.");
+            return;
+        }
+
+        if env::var("CARGO_FEATURE_DOCS_RS").is_err() {
+            println!("cargo:warning=The Julia headers cannot be found, using the dummy bindings");
+        }
+
+        let bindings = fs::read_to_string("dummy-bindings.rs")
             .expect("Couldn't create bindings from dummy bindings.");
+        fs::write(&out_path, dynamic_bindings(&bindings)).expect("Couldn't write bindings!");
         return;
     }
 
-    let flags = flags();
+    let flags = flags(!dynamic);
 
     let functions = vec![
         "jl_alloc_array_1d",
@@ -79,7 +337,13 @@ fn main() {
         "jl_apply_array_type",
         "jl_apply_tuple_type_v",
         "jl_apply_type",
+        "jl_array_del_at",
+        "jl_array_del_end",
         "jl_array_eltype",
+        "jl_array_grow_at",
+        "jl_array_grow_end",
+        "jl_array_sizehint",
+        "jl_arrayset",
         "jl_atexit_hook",
         "jl_box_bool",
         "jl_box_char",
@@ -94,11 +358,13 @@ fn main() {
         "jl_box_uint64",
         "jl_box_uint8",
         "jl_box_voidpointer",
+        "jl_boundp",
         "jl_call",
         "jl_call0",
         "jl_call1",
         "jl_call2",
         "jl_call3",
+        "jl_checked_assignment",
         "jl_compute_fieldtypes",
         "jl_egal",
         "jl_eval_string",
@@ -110,9 +376,11 @@ fn main() {
         "jl_gc_collect",
         "jl_gc_enable",
         "jl_gc_is_enabled",
+        "jl_gc_new_weakref",
         "jl_gc_queue_root",
         "jl_gc_safepoint",
         "jl_get_field",
+        "jl_get_binding_wr",
         "jl_get_global",
         "jl_get_kwsorter",
         "jl_get_nth_field",
@@ -120,6 +388,7 @@ fn main() {
         "jl_get_ptls_states",
         "jl_init__threading",
         "jl_init_with_image__threading",
+        "jl_is_const",
         "jl_is_initialized",
         "jl_isa",
         "jl_islayout_inline",
@@ -155,6 +424,10 @@ fn main() {
         "jl_unbox_uint64",
         "jl_unbox_uint8",
         "jl_unbox_voidpointer",
+        "jl_ver_major",
+        "jl_ver_minor",
+        "jl_ver_patch",
+        "jl_ver_string",
     ];
 
     let mut builder = bindgen::Builder::default()
@@ -167,6 +440,7 @@ fn main() {
     }
 
     let bindings = builder
+        .whitelist_type("jl_binding_t")
         .whitelist_type("jl_code_instance_t")
         .whitelist_type("jl_datatype_t")
         .whitelist_type("jl_expr_t")
@@ -300,4 +574,9 @@ fn main() {
     bindings
         .write_to_file(&out_path)
         .expect("Couldn't write bindings!");
+
+    if dynamic {
+        let bindings = fs::read_to_string(&out_path).expect("Couldn't read bindings!");
+        fs::write(&out_path, dynamic_bindings(&bindings)).expect("Couldn't write bindings!");
+    }
 }
@@ -3896,6 +3896,16 @@ fn bindgen_test_layout__jl_module_t() {
 }
 pub type jl_module_t = _jl_module_t;
 #[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct jl_binding_t {
+    pub name: *mut jl_sym_t,
+    pub value: *mut jl_value_t,
+    pub globalref: *mut jl_value_t,
+    pub owner: *mut _jl_module_t,
+    pub _bitfield_1: __BindgenBitfieldUnit<[u8; 1usize], u8>,
+    pub __bindgen_padding_0: [u8; 7usize],
+}
+#[repr(C)]
 #[derive(Copy, Clone)]
 pub struct _jl_typemap_entry_t {
     pub next: *mut _jl_typemap_entry_t,
@@ -4668,6 +4678,9 @@ pub type jl_gcframe_t = _jl_gcframe_t;
 extern "C" {
     pub fn jl_gc_enable(on: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
 }
+extern "C" {
+    pub fn jl_gc_new_weakref(value: *mut jl_value_t) -> *mut jl_weakref_t;
+}
 extern "C" {
     pub fn jl_gc_is_enabled() -> ::std::os::raw::c_int;
 }
@@ -4834,6 +4847,18 @@ extern "C" {
 extern "C" {
     pub fn jl_unbox_voidpointer(v: *mut jl_value_t) -> *mut ::std::os::raw::c_void;
 }
+extern "C" {
+    pub fn jl_ver_major() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn jl_ver_minor() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn jl_ver_patch() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn jl_ver_string() -> *const ::std::os::raw::c_char;
+}
 extern "C" {
     pub fn jl_field_index(
         t: *mut jl_datatype_t,
@@ -4902,9 +4927,27 @@ extern "C" {
 extern "C" {
     pub fn jl_apply_array_type(type_: *mut jl_value_t, dim: usize) -> *mut jl_value_t;
 }
+extern "C" {
+    pub fn jl_array_grow_end(a: *mut jl_array_t, inc: usize);
+}
+extern "C" {
+    pub fn jl_array_del_end(a: *mut jl_array_t, dec: usize);
+}
+extern "C" {
+    pub fn jl_array_grow_at(a: *mut jl_array_t, idx: isize, inc: usize);
+}
+extern "C" {
+    pub fn jl_array_del_at(a: *mut jl_array_t, idx: isize, dec: usize);
+}
+extern "C" {
+    pub fn jl_array_sizehint(a: *mut jl_array_t, sz: usize);
+}
 extern "C" {
     pub fn jl_array_eltype(a: *mut jl_value_t) -> *mut ::std::os::raw::c_void;
 }
+extern "C" {
+    pub fn jl_arrayset(a: *mut jl_array_t, v: *mut jl_value_t, i: usize);
+}
 extern "C" {
     pub static mut jl_main_module: *mut jl_module_t;
 }
@@ -4914,12 +4957,28 @@ extern "C" {
 extern "C" {
     pub static mut jl_base_module: *mut jl_module_t;
 }
+extern "C" {
+    pub fn jl_get_binding_wr(
+        m: *mut jl_module_t,
+        var: *mut jl_sym_t,
+        error: ::std::os::raw::c_int,
+    ) -> *mut jl_binding_t;
+}
 extern "C" {
     pub fn jl_get_global(m: *mut jl_module_t, var: *mut jl_sym_t) -> *mut jl_value_t;
 }
+extern "C" {
+    pub fn jl_boundp(m: *mut jl_module_t, var: *mut jl_sym_t) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn jl_is_const(m: *mut jl_module_t, var: *mut jl_sym_t) -> ::std::os::raw::c_int;
+}
 extern "C" {
     pub fn jl_set_global(m: *mut jl_module_t, var: *mut jl_sym_t, val: *mut jl_value_t);
 }
+extern "C" {
+    pub fn jl_checked_assignment(b: *mut jl_binding_t, rhs: *mut jl_value_t);
+}
 extern "C" {
     pub fn jl_set_const(m: *mut jl_module_t, var: *mut jl_sym_t, val: *mut jl_value_t);
 }
@@ -0,0 +1,193 @@
+//! Load `libjulia` at runtime.
+//!
+//! If the `dynamic` feature is enabled, `jl-sys` doesn't link with `libjulia` at build time.
+//! Instead, the library must be loaded with [`load_libjulia`] or [`load_default`] before any
+//! function from the Julia C API is called. All functions and globals that are available in this
+//! crate are resolved when the library is loaded, an error is returned if any of them is
+//! missing. The globals are updated after Julia has been initialized with `jl_init` or
+//! `jl_init_with_image__threading`.
+//!
+//! Calling a function from the Julia C API before the library has been loaded causes a panic.
+//!
+//! [`load_libjulia`]: fn.load_libjulia.html
+//! [`load_default`]: fn.load_default.html
+
+use crate::{load_symbols, Symbols};
+use libloading::Library;
+use std::env;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::{Path, PathBuf};
+use std::ptr::addr_of;
+use std::sync::{Mutex, Once};
+
+#[cfg(target_os = "linux")]
+const LIBJULIA: &str = "lib/libjulia.so";
+#[cfg(target_os = "windows")]
+const LIBJULIA: &str = "bin/libjulia.dll";
+#[cfg(target_os = "linux")]
+const JULIA_EXECUTABLE: &str = "julia";
+#[cfg(target_os = "windows")]
+const JULIA_EXECUTABLE: &str = "julia.exe";
+
+struct LibJulia {
+    _lib: Library,
+    symbols: Symbols,
+}
+
+// The handle is only written once, by the closure passed to `LOADED.call_once`.
+static LOADED: Once = Once::new();
+static mut LIBJULIA_HANDLE: Option<LibJulia> = None;
+static LOAD_LOCK: Mutex<()> = Mutex::new(());
+
+/// Errors that can occur while loading `libjulia`.
+#[derive(Debug)]
+pub enum LoadError {
+    /// `libjulia` couldn't be found in `JULIA_DIR` or relative to the `julia` executable.
+    NotFound,
+    /// The library has already been loaded.
+    AlreadyLoaded,
+    /// The library couldn't be opened.
+    Open(PathBuf, String),
+    /// The library doesn't export all required symbols.
+    MissingSymbols(PathBuf, Vec<&'static str>),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            LoadError::NotFound => write!(
+                f,
+                "libjulia cannot be found. Set the JULIA_DIR environment variable or add the julia executable to PATH"
+            ),
+            LoadError::AlreadyLoaded => write!(f, "libjulia has already been loaded"),
+            LoadError::Open(path, reason) => {
+                write!(f, "Cannot open {}: {}", path.display(), reason)
+            }
+            LoadError::MissingSymbols(path, symbols) => write!(
+                f,
+                "{} doesn't export the following symbols: {}",
+                path.display(),
+                symbols.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+/// Load `libjulia` from `path`. Returns an error if the library has already been loaded, if it
+/// can't be opened, or if it doesn't export all functions and globals used by this crate.
+///
+/// Safety: loading a library executes its initialization routines.
+pub unsafe fn load_libjulia<P: AsRef<Path>>(path: P) -> Result<(), LoadError> {
+    let _guard = LOAD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = path.as_ref();
+
+    if is_loaded() {
+        return Err(LoadError::AlreadyLoaded);
+    }
+
+    let lib = open(path).map_err(|e| LoadError::Open(path.to_path_buf(), e.to_string()))?;
+    let symbols = load_symbols(&lib)
+        .map_err(|missing| LoadError::MissingSymbols(path.to_path_buf(), missing))?;
+
+    let mut handle = Some(LibJulia { _lib: lib, symbols });
+    LOADED.call_once(|| LIBJULIA_HANDLE = handle.take());
+
+    match handle {
+        Some(_) => Err(LoadError::AlreadyLoaded),
+        None => Ok(()),
+    }
+}
+
+/// Load `libjulia` from the location returned by [`find_libjulia`]. Returns an error if it
+/// can't be found, or if [`load_libjulia`] fails.
+///
+/// Safety: loading a library executes its initialization routines.
+///
+/// [`find_libjulia`]: fn.find_libjulia.html
+/// [`load_libjulia`]: fn.load_libjulia.html
+pub unsafe fn load_default() -> Result<(), LoadError> {
+    let path = find_libjulia().ok_or(LoadError::NotFound)?;
+    load_libjulia(path)
+}
+
+/// Returns `true` if `libjulia` has been loaded.
+pub fn is_loaded() -> bool {
+    LOADED.is_completed()
+}
+
+/// Find `libjulia`. If the `JULIA_DIR` environment variable is set, the library is expected to
+/// be found in that installation. Otherwise, the installation that contains the `julia`
+/// executable found on the `PATH` is used.
+pub fn find_libjulia() -> Option<PathBuf> {
+    if let Some(julia_dir) = env::var_os("JULIA_DIR") {
+        let path = Path::new(&julia_dir).join(LIBJULIA);
+        return if path.exists() { Some(path) } else { None };
+    }
+
+    let paths = env::var_os("PATH")?;
+    for dir in env::split_paths(&paths) {
+        let executable = dir.join(JULIA_EXECUTABLE);
+        if !executable.is_file() {
+            continue;
+        }
+
+        // The executable on the PATH is often a symlink to ${JULIA_DIR}/bin/julia.
+        let executable = executable.canonicalize().ok()?;
+        let path = executable.parent()?.parent()?.join(LIBJULIA);
+        if path.exists() {
+            return Some(path);
+        }
+    }
+
+    None
+}
+
+#[doc(hidden)]
+pub fn symbols() -> &'static Symbols {
+    match libjulia() {
+        Some(lib) => &lib.symbols,
+        None => panic!("libjulia has not been loaded"),
+    }
+}
+
+fn libjulia() -> Option<&'static LibJulia> {
+    if !LOADED.is_completed() {
+        return None;
+    }
+
+    unsafe { (*addr_of!(LIBJULIA_HANDLE)).as_ref() }
+}
+
+// Julia's symbols must be globally available, otherwise `ccall` can't find them.
+#[cfg(target_os = "linux")]
+unsafe fn open(path: &Path) -> Result<Library, libloading::Error> {
+    use libloading::os::unix::{Library as UnixLibrary, RTLD_GLOBAL, RTLD_NOW};
+    UnixLibrary::open(Some(path), RTLD_NOW | RTLD_GLOBAL).map(Into::into)
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn open(path: &Path) -> Result<Library, libloading::Error> {
+    Library::new(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_nonexistent_library() {
+        unsafe {
+            match load_libjulia("/nonexistent/libjulia.so") {
+                Err(LoadError::Open(path, _)) => {
+                    assert_eq!(path, Path::new("/nonexistent/libjulia.so"))
+                }
+                _ => panic!("Expected LoadError::Open"),
+            }
+        }
+
+        assert!(!is_loaded());
+    }
+}
@@ -0,0 +1,260 @@
+//! Share columnar data with Julia using Apache Arrow.
+//!
+//! This module is only available if the `arrow` feature is enabled. It provides two functions
+//! to move tabular data between an arrow-rs `RecordBatch` and Julia without having to serialize
+//! it.
+//!
+//! A `RecordBatch` can be borrowed by Julia with [`borrow_record_batch`]. The batch is exposed as
+//! a `NamedTuple` of vectors, which is a valid table according to `Tables.jl`. The data of each
+//! column is not copied, the column is a `Jlrs.ReadOnlyVector`, an `AbstractVector{T}` that
+//! borrows the values buffer of the Arrow array and doesn't implement `setindex!`. If a column
+//! contains null values, its validity bitmap is borrowed as well and the column is exposed as a
+//! `Jlrs.MaskedVector`, a read-only `AbstractVector{Union{Missing, T}}` that returns `missing`
+//! for null values.
+//!
+//! The result of `Tables.columntable`, or any other `NamedTuple` of vectors, can be converted to
+//! a `RecordBatch` with [`to_record_batch`]. The data is copied in this direction, columns whose
+//! element type is a union with `Missing` are converted to nullable columns.
+//!
+//! Only columns of primitive numeric types are supported: `Int8` through `Int64`, `UInt8`
+//! through `UInt64`, `Float32`, and `Float64`.
+//!
+//! [`borrow_record_batch`]: fn.borrow_record_batch.html
+//! [`to_record_batch`]: fn.to_record_batch.html
+
+use crate::error::{other, JlrsError, JlrsResult};
+use crate::frame::Output;
+use crate::traits::{private::Internal, Frame, JuliaType, ValidLayout};
+use crate::value::array::Array;
+use crate::value::module::Module;
+use crate::value::Value;
+use ::arrow::array::{Array as ArrowArray, ArrayRef, AsArray, PrimitiveArray};
+use ::arrow::buffer::NullBuffer;
+use ::arrow::datatypes::{
+    ArrowPrimitiveType, DataType as ArrowDataType, Field, Float32Type, Float64Type, Int16Type,
+    Int32Type, Int64Type, Int8Type, Schema, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use ::arrow::error::ArrowError;
+use ::arrow::record_batch::RecordBatch;
+use jl_sys::{jl_apply_array_type, jl_ptr_to_array_1d};
+use std::sync::Arc;
+
+/// Borrows a `RecordBatch` from Rust for use in Julia. The batch is converted to a `NamedTuple`
+/// whose field names are the names of the columns, and whose values are vectors that borrow the
+/// data of these columns.
+///
+/// This function requires one slot on the GC stack for the `NamedTuple`, the columns are created
+/// in a new dynamic frame. Returns an error if there are not enough slots available, or if a
+/// column has an unsupported type.
+pub fn borrow_record_batch<'frame, 'data, F>(
+    frame: &mut F,
+    batch: &'data RecordBatch,
+) -> JlrsResult<Value<'frame, 'data>>
+where
+    F: Frame<'frame>,
+{
+    let output = frame.output()?;
+    borrow_record_batch_output(frame, output, batch)
+}
+
+/// Borrows a `RecordBatch` from Rust for use in Julia using an `Output`. The batch is converted
+/// to a `NamedTuple` whose field names are the names of the columns, and whose values are
+/// vectors that borrow the data of these columns.
+///
+/// Because an `Output` is used, no additional slot in the current frame is used for the
+/// `NamedTuple`, the columns are created in a new dynamic frame. Returns an error if there are
+/// not enough slots available, or if a column has an unsupported type.
+pub fn borrow_record_batch_output<'output, 'frame, 'data, F>(
+    frame: &mut F,
+    output: Output<'output>,
+    batch: &'data RecordBatch,
+) -> JlrsResult<Value<'output, 'data>>
+where
+    F: Frame<'frame>,
+{
+    let schema = batch.schema();
+
+    frame.dynamic_frame(|frame| {
+        let mut names = Vec::with_capacity(batch.num_columns());
+        let mut columns = Vec::with_capacity(batch.num_columns());
+
+        for (field, column) in schema.fields().iter().zip(batch.columns()) {
+            names.push(field.name().as_str());
+            columns.push(borrow_column(frame, column.as_ref())?);
+        }
+
+        let table = Value::new_named_tuple(frame, &mut names, &mut columns)?;
+        Ok(table.extend(frame, output))
+    })
+}
+
+/// Copies a table from Julia to a new `RecordBatch`. The table must be a `NamedTuple` of
+/// vectors, like the result of `Tables.columntable`. All columns must have the same length.
+///
+/// The columns are converted in a new dynamic frame. Returns an error if a column has an
+/// unsupported element type, if the columns have different lengths, or if not enough slots are
+/// available.
+pub fn to_record_batch<'frame, F>(frame: &mut F, table: Value) -> JlrsResult<RecordBatch>
+where
+    F: Frame<'frame>,
+{
+    frame.dynamic_frame(|frame| {
+        let global = frame.global();
+        let jlrs = Module::main(global).submodule("Jlrs")?;
+        let column_data = jlrs.function("columndata")?;
+        let column_validity = jlrs.function("columnvalidity")?;
+
+        let names = table.field_names();
+        let mut fields = Vec::with_capacity(names.len());
+        let mut columns = Vec::with_capacity(names.len());
+
+        for (idx, name) in names.iter().enumerate() {
+            let column = table.get_nth_field(frame, idx)?;
+            let data = column_data
+                .call1(frame, column)?
+                .map_err(|e| JlrsError::Exception(e.type_name().into()))?
+                .cast::<Array>()?;
+            let validity = column_validity
+                .call1(frame, column)?
+                .map_err(|e| JlrsError::Exception(e.type_name().into()))?;
+
+            let nulls = if validity.is_nothing() {
+                None
+            } else {
+                let (validity, _) = validity
+                    .cast::<Array>()?
+                    .copy_inline_data::<bool>()?
+                    .splat();
+                Some(NullBuffer::from(validity))
+            };
+
+            let nullable = nulls.is_some();
+            let column = copy_column(data, nulls)?;
+            fields.push(Field::new(
+                name.as_string(),
+                column.data_type().clone(),
+                nullable,
+            ));
+            columns.push(column);
+        }
+
+        match RecordBatch::try_new(Arc::new(Schema::new(fields)), columns) {
+            Ok(batch) => Ok(batch),
+            Err(e) => other(e),
+        }
+    })
+}
+
+fn borrow_column<'frame, 'data, F>(
+    frame: &mut F,
+    column: &'data dyn ArrowArray,
+) -> JlrsResult<Value<'frame, 'data>>
+where
+    F: Frame<'frame>,
+{
+    match column.data_type() {
+        ArrowDataType::Int8 => borrow_primitive::<Int8Type, _>(frame, column),
+        ArrowDataType::Int16 => borrow_primitive::<Int16Type, _>(frame, column),
+        ArrowDataType::Int32 => borrow_primitive::<Int32Type, _>(frame, column),
+        ArrowDataType::Int64 => borrow_primitive::<Int64Type, _>(frame, column),
+        ArrowDataType::UInt8 => borrow_primitive::<UInt8Type, _>(frame, column),
+        ArrowDataType::UInt16 => borrow_primitive::<UInt16Type, _>(frame, column),
+        ArrowDataType::UInt32 => borrow_primitive::<UInt32Type, _>(frame, column),
+        ArrowDataType::UInt64 => borrow_primitive::<UInt64Type, _>(frame, column),
+        ArrowDataType::Float32 => borrow_primitive::<Float32Type, _>(frame, column),
+        ArrowDataType::Float64 => borrow_primitive::<Float64Type, _>(frame, column),
+        ty => other(ArrowError::NotYetImplemented(format!(
+            "Columns of type {} cannot be borrowed by Julia",
+            ty
+        ))),
+    }
+}
+
+fn borrow_primitive<'frame, 'data, T, F>(
+    frame: &mut F,
+    column: &'data dyn ArrowArray,
+) -> JlrsResult<Value<'frame, 'data>>
+where
+    T: ArrowPrimitiveType,
+    T::Native: JuliaType,
+    F: Frame<'frame>,
+{
+    let column = column.as_primitive::<T>();
+    let jlrs = Module::main(frame.global()).submodule("Jlrs")?;
+
+    unsafe {
+        let data = borrow_slice(frame, column.values())?;
+        let data = jlrs
+            .function("ReadOnlyVector")?
+            .call1(frame, data)?
+            .map_err(|e| JlrsError::Exception(e.type_name().into()))?;
+
+        match column.nulls() {
+            None => Ok(data),
+            Some(nulls) => {
+                let validity = borrow_slice(frame, nulls.inner().values())?;
+                let offset = Value::new(frame, nulls.offset() as isize)?;
+
+                jlrs.function("MaskedVector")?
+                    .call3(frame, data, validity, offset)?
+                    .map_err(|e| JlrsError::Exception(e.type_name().into()).into())
+            }
+        }
+    }
+}
+
+fn copy_column(data: Array, nulls: Option<NullBuffer>) -> JlrsResult<ArrayRef> {
+    if data.contains::<i8>() {
+        copy_primitive::<Int8Type>(data, nulls)
+    } else if data.contains::<i16>() {
+        copy_primitive::<Int16Type>(data, nulls)
+    } else if data.contains::<i32>() {
+        copy_primitive::<Int32Type>(data, nulls)
+    } else if data.contains::<i64>() {
+        copy_primitive::<Int64Type>(data, nulls)
+    } else if data.contains::<u8>() {
+        copy_primitive::<UInt8Type>(data, nulls)
+    } else if data.contains::<u16>() {
+        copy_primitive::<UInt16Type>(data, nulls)
+    } else if data.contains::<u32>() {
+        copy_primitive::<UInt32Type>(data, nulls)
+    } else if data.contains::<u64>() {
+        copy_primitive::<UInt64Type>(data, nulls)
+    } else if data.contains::<f32>() {
+        copy_primitive::<Float32Type>(data, nulls)
+    } else if data.contains::<f64>() {
+        copy_primitive::<Float64Type>(data, nulls)
+    } else {
+        other(ArrowError::NotYetImplemented(format!(
+            "Columns with elements of type {} cannot be converted to Arrow",
+            data.element_type().type_name()
+        )))
+    }
+}
+
+fn copy_primitive<T>(data: Array, nulls: Option<NullBuffer>) -> JlrsResult<ArrayRef>
+where
+    T: ArrowPrimitiveType,
+    T::Native: ValidLayout,
+{
+    let (values, _) = data.copy_inline_data::<T::Native>()?.splat();
+    match PrimitiveArray::<T>::try_new(values.into(), nulls) {
+        Ok(column) => Ok(Arc::new(column)),
+        Err(e) => other(e),
+    }
+}
+
+// The data is borrowed immutably, the returned array must only be exposed to Julia wrapped in a
+// `Jlrs.ReadOnlyVector`.
+unsafe fn borrow_slice<'frame, 'data, T, F>(
+    frame: &mut F,
+    data: &'data [T],
+) -> JlrsResult<Value<'frame, 'data>>
+where
+    T: JuliaType,
+    F: Frame<'frame>,
+{
+    let array_type = jl_apply_array_type(T::julia_type().cast(), 1);
+    let array = jl_ptr_to_array_1d(array_type, data.as_ptr() as *mut _, data.len(), 0);
+    frame.protect(array.cast(), Internal).map_err(Into::into)
+}
@@ -0,0 +1,345 @@
+//! Diagnose how the GC stack is used.
+//!
+//! This module is only available if the `debug-stack` feature is enabled. When it's enabled,
+//! jlrs records which frames exist on each GC stack, where they have been created, and where
+//! each of their slots has been allocated. It also keeps track of the peak usage of each stack.
+//! This information can be used to choose the `stack_size` when Julia is initialized and to find
+//! out why a frame or the stack has run out of space.
+//!
+//! Locations are recorded with `#[track_caller]`. Most methods that allocate a frame or slot
+//! directly report the location where they're called from your code, other methods report the
+//! location inside jlrs where the slot has been allocated.
+//!
+//! When the stack or a frame runs out of space, a [`StackReport`] of the stack at that moment is
+//! stored. You can retrieve it with [`alloc_error_report`]:
+//!
+//! ```no_run
+//! # use jlrs::prelude::*;
+//! # use jlrs::debug_stack;
+//! # fn main() {
+//! # let mut julia = unsafe { Julia::init(16).unwrap() };
+//! let res = julia.frame(1, |_global, frame| {
+//!     let _a = Value::new(frame, 1usize)?;
+//!     let _b = Value::new(frame, 2usize)?;
+//!     Ok(())
+//! });
+//!
+//! if res.is_err() {
+//!     eprintln!("{}", debug_stack::alloc_error_report().unwrap());
+//! }
+//!
+//! println!("Peak usage: {:?}", debug_stack::last_peak());
+//! # }
+//! ```
+//!
+//! Recording this information is relatively expensive, this feature should only be enabled while
+//! debugging.
+//!
+//! [`StackReport`]: struct.StackReport.html
+//! [`alloc_error_report`]: fn.alloc_error_report.html
+
+use crate::traits::{private::Internal, Frame};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::panic::Location;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static MAX_PEAK: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static RECORDER: RefCell<Recorder> = RefCell::new(Recorder::default());
+}
+
+/// The kind of a recorded frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameKind {
+    /// A `StaticFrame` with the given capacity.
+    Static(usize),
+    /// A `DynamicFrame` or `AsyncFrame`.
+    Dynamic,
+}
+
+/// A frame that exists on a GC stack.
+#[derive(Clone, Debug)]
+pub struct FrameReport {
+    /// The kind of frame.
+    pub kind: FrameKind,
+    /// The location where the frame has been created.
+    pub created_at: &'static Location<'static>,
+    /// The location where each slot of the frame has been allocated, or `None` if it's unused.
+    pub slots: Vec<Option<&'static Location<'static>>>,
+}
+
+impl FrameReport {
+    /// Returns the number of slots that are in use.
+    pub fn used(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+}
+
+/// The state of a GC stack.
+#[derive(Clone, Debug)]
+pub struct StackReport {
+    /// The total number of slots of the stack.
+    pub stack_size: usize,
+    /// The number of slots that are in use, including the slots taken by the frames themselves.
+    pub used: usize,
+    /// The largest number of slots that has been in use since the outermost frame was created.
+    pub peak: usize,
+    /// The frames that exist on the stack, from the outermost to the innermost frame.
+    pub frames: Vec<FrameReport>,
+}
+
+impl Display for StackReport {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        writeln!(
+            f,
+            "GC stack: {} of {} slots in use, peak usage {}",
+            self.used, self.stack_size, self.peak
+        )?;
+
+        for (depth, frame) in self.frames.iter().enumerate() {
+            match frame.kind {
+                FrameKind::Static(capacity) => writeln!(
+                    f,
+                    "  #{} StaticFrame created at {}: {} of {} slots in use",
+                    depth,
+                    frame.created_at,
+                    frame.used(),
+                    capacity
+                )?,
+                FrameKind::Dynamic => writeln!(
+                    f,
+                    "  #{} DynamicFrame created at {}: {} slots in use",
+                    depth,
+                    frame.created_at,
+                    frame.used()
+                )?,
+            }
+
+            for (idx, slot) in frame.slots.iter().enumerate() {
+                if let Some(location) = slot {
+                    writeln!(f, "      slot {}: {}", idx, location)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a report of the GC stack that has been used most recently on this thread, or `None`
+/// if no frame exists on that stack. Tasks that run on the same thread share it, use
+/// [`frame_report`] to get the report of the stack of a specific task.
+///
+/// [`frame_report`]: fn.frame_report.html
+pub fn report() -> Option<StackReport> {
+    RECORDER.with(|recorder| {
+        let recorder = recorder.borrow();
+        let stack = recorder.stacks.get(&recorder.current?)?;
+        Some(stack.report())
+    })
+}
+
+/// Returns a report of the GC stack `frame` has been created on, or `None` if `frame` is a
+/// `NullFrame`. Every [`JuliaTask`] has its own stack, so calling this function with the task's
+/// frame at the end of [`JuliaTask::run`] returns the peak usage of that task even if other tasks
+/// run concurrently.
+///
+/// [`JuliaTask`]: ../traits/multitask/trait.JuliaTask.html
+/// [`JuliaTask::run`]: ../traits/multitask/trait.JuliaTask.html#tymethod.run
+pub fn frame_report<'frame, F: Frame<'frame>>(frame: &F) -> Option<StackReport> {
+    frame.stack_report(Internal)
+}
+
+/// Returns a report of the GC stack at the moment the last `AllocError` was created on this
+/// thread, or `None` if no such error has occurred. The report is removed.
+pub fn alloc_error_report() -> Option<StackReport> {
+    RECORDER.with(|recorder| recorder.borrow_mut().alloc_error.take())
+}
+
+/// Returns the peak usage of the GC stack during the last outermost frame that has been dropped
+/// on this thread. An outermost frame is created when you call [`Julia::frame`] or
+/// [`Julia::dynamic_frame`], or when a [`JuliaTask`] is run. Because tasks run concurrently,
+/// the peak usage of a specific task should be retrieved with [`frame_report`] instead.
+///
+/// [`Julia::frame`]: ../struct.Julia.html#method.frame
+/// [`Julia::dynamic_frame`]: ../struct.Julia.html#method.dynamic_frame
+/// [`JuliaTask`]: ../traits/multitask/trait.JuliaTask.html
+/// [`frame_report`]: fn.frame_report.html
+pub fn last_peak() -> Option<usize> {
+    RECORDER.with(|recorder| recorder.borrow().last_peak)
+}
+
+/// Returns the peak usage of all GC stacks on all threads since Julia has been initialized.
+pub fn max_peak() -> usize {
+    MAX_PEAK.load(Ordering::Relaxed)
+}
+
+#[derive(Default)]
+struct Recorder {
+    stacks: HashMap<usize, StackRecord>,
+    current: Option<usize>,
+    alloc_error: Option<StackReport>,
+    last_peak: Option<usize>,
+}
+
+struct StackRecord {
+    size: usize,
+    used: usize,
+    peak: usize,
+    frames: Vec<FrameRecord>,
+}
+
+struct FrameRecord {
+    idx: usize,
+    report: FrameReport,
+}
+
+impl StackRecord {
+    fn report(&self) -> StackReport {
+        StackReport {
+            stack_size: self.size,
+            used: self.used,
+            peak: self.peak,
+            frames: self
+                .frames
+                .iter()
+                .map(|frame| frame.report.clone())
+                .collect(),
+        }
+    }
+
+    fn frame_mut(&mut self, idx: usize) -> Option<&mut FrameRecord> {
+        self.frames.iter_mut().rev().find(|frame| frame.idx == idx)
+    }
+}
+
+fn with_stack<T, F: FnOnce(&mut StackRecord) -> T>(stack: &[*mut c_void], func: F) -> T {
+    RECORDER.with(|recorder| {
+        let mut recorder = recorder.borrow_mut();
+        let recorder = &mut *recorder;
+        let key = stack.as_ptr() as usize;
+        recorder.current = Some(key);
+
+        let record = recorder.stacks.entry(key).or_insert_with(|| StackRecord {
+            size: stack.len(),
+            used: 0,
+            peak: 0,
+            frames: Vec::new(),
+        });
+
+        // The address of a stack can be reused by a new stack with a different size.
+        record.size = stack.len();
+        record.used = stack[0] as usize;
+        record.peak = record.peak.max(record.used);
+        MAX_PEAK.fetch_max(record.peak, Ordering::Relaxed);
+
+        let res = func(record);
+        if record.frames.is_empty() {
+            recorder.last_peak = Some(record.peak);
+            recorder.stacks.remove(&key);
+            recorder.current = None;
+        }
+
+        res
+    })
+}
+
+#[track_caller]
+pub(crate) fn push_frame(stack: &[*mut c_void], idx: usize, capacity: Option<usize>) {
+    let created_at = Location::caller();
+    with_stack(stack, |record| {
+        let (kind, slots) = match capacity {
+            Some(capacity) => (FrameKind::Static(capacity), vec![None; capacity]),
+            None => (FrameKind::Dynamic, Vec::new()),
+        };
+
+        record.frames.push(FrameRecord {
+            idx,
+            report: FrameReport {
+                kind,
+                created_at,
+                slots,
+            },
+        });
+    })
+}
+
+pub(crate) fn pop_frame(stack: &[*mut c_void], idx: usize) {
+    with_stack(stack, |record| {
+        if let Some(pos) = record.frames.iter().rposition(|frame| frame.idx == idx) {
+            record.frames.truncate(pos);
+        }
+    })
+}
+
+#[track_caller]
+pub(crate) fn push_slot(stack: &[*mut c_void], idx: usize) {
+    let location = Location::caller();
+    with_stack(stack, |record| {
+        if let Some(frame) = record.frame_mut(idx) {
+            frame.report.slots.push(Some(location));
+        }
+    })
+}
+
+#[track_caller]
+pub(crate) fn set_slot(stack: &[*mut c_void], idx: usize, offset: usize) {
+    let location = Location::caller();
+    with_stack(stack, |record| {
+        if let Some(slot) = record
+            .frame_mut(idx)
+            .and_then(|frame| frame.report.slots.get_mut(offset))
+        {
+            *slot = Some(location);
+        }
+    })
+}
+
+pub(crate) fn clear_slots(stack: &[*mut c_void], idx: usize, from: usize) {
+    with_stack(stack, |record| {
+        if let Some(frame) = record.frame_mut(idx) {
+            match frame.report.kind {
+                FrameKind::Static(_) => frame.report.slots[from..]
+                    .iter_mut()
+                    .for_each(|slot| *slot = None),
+                FrameKind::Dynamic => frame.report.slots.truncate(from),
+            }
+        }
+    })
+}
+
+pub(crate) fn stack_report(stack: &[*mut c_void]) -> Option<StackReport> {
+    RECORDER.with(|recorder| {
+        let key = stack.as_ptr() as usize;
+        recorder.borrow().stacks.get(&key).map(StackRecord::report)
+    })
+}
+
+pub(crate) fn record_alloc_error() {
+    RECORDER.with(|recorder| {
+        let mut recorder = recorder.borrow_mut();
+        let report = recorder
+            .current
+            .and_then(|key| recorder.stacks.get(&key))
+            .map(StackRecord::report);
+
+        if report.is_some() {
+            recorder.alloc_error = report;
+        }
+    })
+}
+
+pub(crate) fn record_empty_stack_error(stack: &[*mut c_void]) {
+    RECORDER.with(|recorder| {
+        recorder.borrow_mut().alloc_error = Some(StackReport {
+            stack_size: stack.len(),
+            used: 0,
+            peak: 0,
+            frames: Vec::new(),
+        });
+    })
+}
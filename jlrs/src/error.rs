@@ -3,6 +3,7 @@
 use crate::value::array::Dimensions;
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::Range;
 
 /// Alias that is used for most `Result`s in this crate.
 pub type JlrsResult<T> = Result<T, Box<JlrsError>>;
@@ -37,6 +38,7 @@ pub enum JlrsError {
     NotAUnion,
     InvalidBody(String),
     NotAKind(String),
+    NotAType(String),
     NotAUnionAll,
     FunctionNotFound(String),
     IncludeNotFound(String),
@@ -60,6 +62,20 @@ pub enum JlrsError {
     NotSubtype,
     NotConcrete(String),
     NamedTupleSizeMismatch(usize, usize),
+    NotAVector(usize),
+    SharedArray,
+    BorrowedArray,
+    InvalidView(Vec<Range<usize>>, Dimensions),
+    InvalidAxis(usize, usize),
+    IncompatibleVersion(String, String),
+    LibJuliaNotLoaded,
+    TooManyThreads(usize, usize),
+    ConstGlobal(String),
+    ImportedGlobal(String, String),
+    NotTracked(String),
+    ForeignThread(String),
+    UndefRef,
+    Unsupported(String, String),
 }
 
 /// Create a new `JlrsError::Exception` and wrap it in a `JlrsResult::Err`.
@@ -83,7 +99,7 @@ impl JlrsError {
     }
 
     pub(crate) fn alloc_error(a: AllocError) -> Self {
-        JlrsError::AllocError(a)
+        a.into()
     }
 }
 
@@ -145,6 +161,7 @@ impl Display for JlrsError {
 
             JlrsError::NotAMethodInstance => write!(formatter, "This is not a method instance"),
             JlrsError::NotAKind(kind) => write!(formatter, "The type {} is not a kind", kind),
+            JlrsError::NotAType(ty) => write!(formatter, "A value of type {} is not a type", ty),
             JlrsError::InvalidBody(body_ty) => write!(
                 formatter,
                 "The body of a UnionAll must be a type or a TypeVar. Found: {}",
@@ -203,6 +220,60 @@ impl Display for JlrsError {
                     values
                 )
             }
+            JlrsError::NotAVector(n) => write!(
+                formatter,
+                "Only one-dimensional arrays can be resized, this array has {} dimensions",
+                n
+            ),
+            JlrsError::SharedArray => {
+                write!(formatter, "Cannot resize an array that shares its data")
+            }
+            JlrsError::BorrowedArray => {
+                write!(
+                    formatter,
+                    "Cannot resize an array that borrows its data from Rust"
+                )
+            }
+            JlrsError::InvalidView(ranges, sz) => write!(
+                formatter,
+                "Ranges {:?} are not valid for array with shape {}",
+                ranges, sz
+            ),
+            JlrsError::InvalidAxis(axis, n) => write!(
+                formatter,
+                "Axis {} is not valid for array with {} dimensions",
+                axis, n
+            ),
+            JlrsError::IncompatibleVersion(expected, found) => write!(
+                formatter,
+                "jlrs has been built for Julia {}, but Julia {} has been loaded",
+                expected, found
+            ),
+            JlrsError::LibJuliaNotLoaded => write!(formatter, "libjulia has not been loaded"),
+            JlrsError::ConstGlobal(name) => {
+                write!(formatter, "The global {} is a constant", name)
+            }
+            JlrsError::ImportedGlobal(name, owner) => write!(
+                formatter,
+                "The global {} has been imported from {} and can't be changed",
+                name, owner
+            ),
+            JlrsError::ForeignThread(version) => write!(
+                formatter,
+                "Julia has been initialized on another thread. Julia {} can't adopt threads, only \
+                 the thread that initialized Julia can call into it",
+                version
+            ),
+            JlrsError::UndefRef => write!(formatter, "This reference is undefined"),
+            JlrsError::Unsupported(feature, version) => {
+                write!(formatter, "{} is not supported by Julia {}", feature, version)
+            }
+            JlrsError::NotTracked(path) => write!(formatter, "The file {} is not tracked", path),
+            JlrsError::TooManyThreads(n, n_julia) => write!(
+                formatter,
+                "The async runtime can't use {} threads because Julia has been started with {} threads, set JULIA_NUM_THREADS to at least {}",
+                n, n_julia, n + 1
+            ),
         }
     }
 }
@@ -226,6 +297,9 @@ pub enum AllocError {
 
 impl Into<JlrsError> for AllocError {
     fn into(self) -> JlrsError {
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::record_alloc_error();
+
         JlrsError::AllocError(self)
     }
 }
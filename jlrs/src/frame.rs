@@ -17,7 +17,7 @@
 //! The final type, [`AsyncFrame`] is only available when you use the async runtime. Structs that
 //! implement [`JuliaTask`] can use this kind of frame in the `run`-method. It's essentially a
 //! [`DynamicFrame`] with the additional feature that it can be used to call
-//! [`Value::call_async`].
+//! [`Value::call_async`]. A nested `AsyncFrame` can be created with [`AsyncFrame::async_frame`].
 //!
 //! Frames have a lifetime, `'frame`. This lifetime ensures that a [`Value`] can only be used as
 //! long as the frame that protects it has not been dropped.
@@ -28,6 +28,7 @@
 //! [`DynamicFrame`]: struct.DynamicFrame.html
 //! [`NullFrame`]: struct.NullFrame.html
 //! [`AsyncFrame`]: struct.AsyncFrame.html
+//! [`AsyncFrame::async_frame`]: struct.AsyncFrame.html#method.async_frame
 //! [`Value`]: ../value/struct.Value.html
 //! [`Value::call_async`]: ../value/struct.Value.html#method.call_async
 //! [`Frame`]: ../traits/trait.Frame.html
@@ -39,7 +40,10 @@ use crate::mode::Async;
 use crate::mode::Mode;
 use crate::stack::{Dynamic, StackView, Static};
 use crate::CCall;
+#[cfg(all(feature = "async", target_os = "linux"))]
+use futures::future::LocalBoxFuture;
 use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 
 #[derive(Copy, Clone, Default)]
 pub struct FrameIdx(pub(crate) usize);
@@ -79,6 +83,7 @@ impl<'frame, M: Mode> StaticFrame<'frame, M> {
         }
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub(crate) unsafe fn nested_frame<'nested>(
         &'nested mut self,
         capacity: usize,
@@ -96,6 +101,53 @@ impl<'frame, M: Mode> StaticFrame<'frame, M> {
     pub fn capacity(&self) -> usize {
         self.capacity
     }
+
+    /// Call the given closure with a scoped version of this frame. The scoped frame uses the
+    /// free slots of this frame, when the closure returns these slots are cleared and can be
+    /// reused. Unlike [`Frame::frame`], no new frame is pushed to the GC stack so no slots are
+    /// taken. The closure can't return values that are protected by the scoped frame, this lets
+    /// you create temporary values in a loop without running out of slots:
+    ///
+    /// ```no_run
+    /// # use jlrs::prelude::*;
+    /// # fn main() {
+    /// # let mut julia = unsafe { Julia::init(16).unwrap() };
+    /// julia.frame(2, |global, frame| {
+    ///     let func = Module::base(global).function("sqrt")?;
+    ///     let mut sum = 0.0;
+    ///
+    ///     for i in 0..1000 {
+    ///         sum += frame.scope(|frame| {
+    ///             let i = Value::new(frame, i as f64)?;
+    ///             func.call1(frame, i)?.unwrap().cast::<f64>()
+    ///         })?;
+    ///     }
+    ///
+    ///     Ok(sum)
+    /// }).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`Frame::frame`]: ../traits/trait.Frame.html#method.frame
+    pub fn scope<T, F>(&mut self, func: F) -> JlrsResult<T>
+    where
+        F: for<'scope> FnOnce(&mut StaticFrame<'scope, M>) -> JlrsResult<T>,
+    {
+        unsafe {
+            let len = self.len;
+            let mut frame = ManuallyDrop::new(StaticFrame {
+                idx: self.idx,
+                memory: self.memory.nest_static(),
+                capacity: self.capacity,
+                len,
+            });
+
+            let res = func(&mut frame);
+            let scoped_len = frame.len;
+            self.memory.clear(self.idx, len, scoped_len);
+            res
+        }
+    }
 }
 
 impl<'frame, U> Drop for StaticFrame<'frame, U>
@@ -138,6 +190,7 @@ impl<'frame, M: Mode> DynamicFrame<'frame, M> {
         }
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub(crate) unsafe fn nested_frame<'nested>(
         &'nested mut self,
     ) -> JlrsResult<DynamicFrame<'nested, M>> {
@@ -148,6 +201,32 @@ impl<'frame, M: Mode> DynamicFrame<'frame, M> {
             len: 0,
         })
     }
+
+    /// Call the given closure with a scoped version of this frame. The scoped frame grows this
+    /// frame, when the closure returns this frame is shrunk to its original size. Unlike
+    /// [`Frame::dynamic_frame`], no new frame is pushed to the GC stack so no slots are taken.
+    /// The closure can't return values that are protected by the scoped frame. See
+    /// [`StaticFrame::scope`] for an example.
+    ///
+    /// [`Frame::dynamic_frame`]: ../traits/trait.Frame.html#method.dynamic_frame
+    /// [`StaticFrame::scope`]: struct.StaticFrame.html#method.scope
+    pub fn scope<T, F>(&mut self, func: F) -> JlrsResult<T>
+    where
+        F: for<'scope> FnOnce(&mut DynamicFrame<'scope, M>) -> JlrsResult<T>,
+    {
+        unsafe {
+            let len = self.len;
+            let mut frame = ManuallyDrop::new(DynamicFrame {
+                idx: self.idx,
+                memory: self.memory.nest_dynamic(),
+                len,
+            });
+
+            let res = func(&mut frame);
+            self.memory.truncate(self.idx, len);
+            res
+        }
+    }
 }
 
 impl<'frame, U> Drop for DynamicFrame<'frame, U>
@@ -209,6 +288,7 @@ pub struct AsyncFrame<'frame> {
 
 #[cfg(all(feature = "async", target_os = "linux"))]
 impl<'frame> AsyncFrame<'frame> {
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub(crate) unsafe fn nested_frame<'nested>(
         &'nested mut self,
     ) -> JlrsResult<DynamicFrame<'nested, Async>> {
@@ -219,6 +299,70 @@ impl<'frame> AsyncFrame<'frame> {
             len: 0,
         })
     }
+
+    /// Create a nested `AsyncFrame` and call the given closure, the future it returns is awaited
+    /// and its result is returned. In contrast to [`Frame::frame`] and [`Frame::dynamic_frame`],
+    /// the nested frame can be used to call [`Value::call_async`]. The values that are created
+    /// with the nested frame are popped from the GC stack when this method returns, this lets a
+    /// task call [`Value::call_async`] in a loop without running out of space. The number of
+    /// required slots on the stack is `2`.
+    ///
+    /// Because closures can't be async, the closure must return a boxed future:
+    ///
+    /// ```no_run
+    /// # use jlrs::prelude::*;
+    /// # struct MyTask;
+    /// # #[async_trait(?Send)]
+    /// # impl JuliaTask for MyTask {
+    /// #     type T = f64;
+    /// #     type R = crossbeam_channel::Sender<JlrsResult<f64>>;
+    /// async fn run<'base>(
+    ///     &mut self,
+    ///     global: Global<'base>,
+    ///     frame: &mut AsyncFrame<'base>,
+    /// ) -> JlrsResult<Self::T> {
+    ///     let func = Module::base(global).function("sqrt")?;
+    ///     let mut sum = 0.0;
+    ///
+    ///     for i in 0..1000 {
+    ///         sum += frame
+    ///             .async_frame(|frame| {
+    ///                 Box::pin(async move {
+    ///                     let i = Value::new(frame, i as f64)?;
+    ///                     func.call_async(frame, &mut [i])
+    ///                         .await?
+    ///                         .unwrap()
+    ///                         .cast::<f64>()
+    ///                 })
+    ///             })
+    ///             .await?;
+    ///     }
+    ///
+    ///     Ok(sum)
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// Returns an error if there is not enough space on the stack.
+    ///
+    /// [`Frame::frame`]: ../traits/trait.Frame.html#method.frame
+    /// [`Frame::dynamic_frame`]: ../traits/trait.Frame.html#method.dynamic_frame
+    /// [`Value::call_async`]: ../value/struct.Value.html#method.call_async
+    pub async fn async_frame<'nested, T, F>(&'nested mut self, func: F) -> JlrsResult<T>
+    where
+        F: for<'inner> FnOnce(
+            &'inner mut AsyncFrame<'nested>,
+        ) -> LocalBoxFuture<'inner, JlrsResult<T>>,
+    {
+        let idx = unsafe { self.memory.new_frame()? };
+        let mut frame = AsyncFrame {
+            idx,
+            memory: unsafe { self.memory.nest_dynamic() },
+            len: 0,
+        };
+
+        func(&mut frame).await
+    }
 }
 
 #[cfg(all(feature = "async", target_os = "linux"))]
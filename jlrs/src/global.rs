@@ -16,8 +16,9 @@ pub struct Global<'base>(PhantomData<&'base ()>);
 
 impl<'base> Global<'base> {
     #[doc(hidden)]
-
+    #[track_caller]
     pub unsafe fn new() -> Self {
+        crate::assert_julia_thread();
         Global(PhantomData)
     }
 }
@@ -0,0 +1,103 @@
+//! Information about the running instance of Julia.
+//!
+//! [`Julia::info`] returns a [`JuliaInfo`], which contains the version of Julia, the number of
+//! threads that are available and the options Julia has been started with.
+//!
+//! [`Julia::info`]: ../struct.Julia.html#method.info
+//! [`JuliaInfo`]: struct.JuliaInfo.html
+
+use crate::error::{exception, JlrsResult};
+use crate::global::Global;
+use crate::traits::Frame;
+use crate::value::module::Module;
+use crate::{version, JuliaVersion};
+use jl_sys::jl_ver_string;
+use std::ffi::CStr;
+use std::path::PathBuf;
+
+/// Information about the running instance of Julia.
+#[derive(Clone, Debug)]
+pub struct JuliaInfo {
+    /// The version of Julia.
+    pub version: JuliaVersion,
+    /// The full version string, e.g. `"1.5.3"` or `"1.6.0-rc1"`.
+    pub version_string: String,
+    /// The number of logical CPU cores, `Sys.CPU_THREADS`.
+    pub cpu_threads: usize,
+    /// The number of threads Julia can use, `Threads.nthreads()`.
+    pub n_threads: usize,
+    /// The path to the system image that has been loaded.
+    pub sysimage_path: PathBuf,
+    /// The directory that contains the Julia executable, `Sys.BINDIR`.
+    pub bindir: PathBuf,
+    /// The number of bits in a machine word, `Sys.WORD_SIZE`.
+    pub word_size: usize,
+    /// The options Julia has been started with.
+    pub options: JuliaOptions,
+}
+
+/// Some of the options Julia has been started with, these correspond to fields of `jl_options`.
+/// See `Base.JLOptions` for more information.
+#[derive(Copy, Clone, Debug)]
+pub struct JuliaOptions {
+    /// The number of threads that has been requested with the `--threads` option, `0` if this
+    /// option hasn't been used.
+    pub n_threads: i32,
+    /// The optimization level, `-O`.
+    pub opt_level: i8,
+    /// The debug level, `-g`.
+    pub debug_level: i8,
+    /// The `--check-bounds` option, `0` is the default, `1` is `yes`, `2` is `no`.
+    pub check_bounds: i8,
+    /// The `--compile` option, `0` is `no`, `1` is `yes`, `2` is `all`, `3` is `min`.
+    pub compile_enabled: i8,
+    /// Whether Julia handles signals.
+    pub handle_signals: bool,
+    /// Whether the startup file is loaded.
+    pub startup_file: bool,
+}
+
+impl JuliaInfo {
+    pub(crate) fn new<'frame, F>(global: Global<'frame>, frame: &mut F) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+    {
+        let version = version()?;
+        let version_string = unsafe {
+            CStr::from_ptr(jl_ver_string())
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        frame.frame(13, |frame| {
+            let info = Module::main(global)
+                .submodule("Jlrs")?
+                .function("info")?
+                .call0(frame)?;
+
+            let info = match info {
+                Ok(info) => info,
+                Err(exc) => return exception(exc.type_name().into()),
+            };
+
+            Ok(JuliaInfo {
+                version,
+                version_string,
+                cpu_threads: info.get_nth_field(frame, 0)?.cast::<usize>()?,
+                n_threads: info.get_nth_field(frame, 1)?.cast::<usize>()?,
+                sysimage_path: info.get_nth_field(frame, 2)?.cast::<String>()?.into(),
+                bindir: info.get_nth_field(frame, 3)?.cast::<String>()?.into(),
+                word_size: info.get_nth_field(frame, 4)?.cast::<usize>()?,
+                options: JuliaOptions {
+                    n_threads: info.get_nth_field(frame, 5)?.cast::<i32>()?,
+                    opt_level: info.get_nth_field(frame, 6)?.cast::<i8>()?,
+                    debug_level: info.get_nth_field(frame, 7)?.cast::<i8>()?,
+                    check_bounds: info.get_nth_field(frame, 8)?.cast::<i8>()?,
+                    compile_enabled: info.get_nth_field(frame, 9)?.cast::<i8>()?,
+                    handle_signals: info.get_nth_field(frame, 10)?.cast::<bool>()?,
+                    startup_file: info.get_nth_field(frame, 11)?.cast::<bool>()?,
+                },
+            })
+        })
+    }
+}
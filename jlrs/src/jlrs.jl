@@ -51,8 +51,209 @@ function attachstacktrace(func::Function)::Function
     wrapper
 end
 
+function importnames(to::Module, from::Module, names::Symbol...)
+    for name in names
+        ccall(:jl_module_import, Cvoid, (Any, Any, Any), to, from, name)
+    end
+end
+
+function usingmodule(to::Module, from::Module)
+    ccall(:jl_module_using, Cvoid, (Any, Any), to, from)
+end
+
+function modulenames(m::Module, all::Bool, imported::Bool)
+    names(m, all=all, imported=imported)
+end
+
+function submodules(m::Module)
+    modules = Module[]
+    for name in names(m, all=true)
+        if isdefined(m, name)
+            value = getfield(m, name)
+            if value isa Module && value !== m && parentmodule(value) === m
+                push!(modules, value)
+            end
+        end
+    end
+
+    modules
+end
+
+function binding(m::Module, name::Symbol)
+    owner = Base.binding_module(m, name)
+    (owner, Base.isexported(owner, name), getfield(m, name))
+end
+
+# Modules that contain the code of tracked files, keyed by path. Modules that have been replaced
+# by reloading a file are kept alive so values that have been obtained from them remain valid.
+const tracked = Dict{String,Module}()
+const replaced = Module[]
+
+function includetracked(path::String, name::Symbol)
+    m = Module(name)
+    Core.eval(m, :(eval(x) = Core.eval($m, x)))
+    Core.eval(m, :(include(path) = Base.include($m, path)))
+    Base.include(m, path)
+
+    if haskey(tracked, path)
+        push!(replaced, tracked[path])
+    end
+
+    tracked[path] = m
+    nothing
+end
+
+trackedmodule(path::String) = tracked[path]
+
+function fingerprints(m::Module)
+    prints = Dict{Symbol,UInt}()
+    for name in names(m, all=true)
+        if !isdefined(m, name) || startswith(string(name), "#") || name in (:eval, :include)
+            continue
+        end
+
+        f = getfield(m, name)
+        if !(f isa Function) || parentmodule(f) !== m
+            continue
+        end
+
+        h = hash(name)
+        for method in sort(collect(methods(f)), by=method -> string(method.sig))
+            h = hash(string(method.sig), h)
+            try
+                # Line numbers are not part of the statements, so moving a function around
+                # doesn't change its fingerprint.
+                h = hash(map(string, Base.uncompressed_ast(method).code), h)
+            catch
+            end
+        end
+
+        prints[name] = h
+    end
+
+    prints
+end
+
+function reloadtracked(path::String, name::Symbol)
+    old = fingerprints(tracked[path])
+    includetracked(path, name)
+    new = fingerprints(tracked[path])
+
+    changed = Symbol[]
+    for (name, print) in new
+        if get(old, name, nothing) != print
+            push!(changed, name)
+        end
+    end
+
+    for name in keys(old)
+        if !haskey(new, name)
+            push!(changed, name)
+        end
+    end
+
+    sort!(changed)
+end
+
+function info()
+    opts = Base.JLOptions()
+    image_file = opts.image_file == C_NULL ? "" : unsafe_string(opts.image_file)
+
+    (
+        UInt(Sys.CPU_THREADS),
+        UInt(Threads.nthreads()),
+        image_file,
+        Sys.BINDIR,
+        UInt(Sys.WORD_SIZE),
+        Int32(opts.nthreads),
+        opts.opt_level,
+        opts.debug_level,
+        opts.check_bounds,
+        opts.compile_enabled,
+        opts.handle_signals != 0,
+        opts.startupfile != 2,
+    )
+end
+
 function clean(a::Array)
     @assert droparray[] != C_NULL "droparray is null"
     ccall(droparray[], Cvoid, (Array,), a)
 end
+
+# A vector that borrows immutable data from Rust, it doesn't implement setindex!.
+struct ReadOnlyVector{T} <: AbstractVector{T}
+    data::Vector{T}
+end
+
+Base.size(v::ReadOnlyVector) = size(v.data)
+Base.IndexStyle(::Type{<:ReadOnlyVector}) = IndexLinear()
+Base.@propagate_inbounds Base.getindex(v::ReadOnlyVector, i::Int) = v.data[i]
+
+struct MaskedVector{T} <: AbstractVector{Union{Missing,T}}
+    data::ReadOnlyVector{T}
+    validity::Vector{UInt8}
+    offset::Int
+end
+
+Base.size(v::MaskedVector) = size(v.data)
+Base.IndexStyle(::Type{<:MaskedVector}) = IndexLinear()
+
+function Base.getindex(v::MaskedVector, i::Int)
+    @boundscheck checkbounds(v, i)
+    bit = v.offset + i - 1
+    if v.validity[bit >> 3 + 1] & (0x01 << (bit & 7)) != 0x00
+        @inbounds v.data[i]
+    else
+        missing
+    end
+end
+
+columndata(v::ReadOnlyVector) = v.data
+
+function columndata(v::AbstractVector)
+    T = nonmissingtype(eltype(v))
+    v isa Vector{T} && return v
+
+    data = Vector{T}(undef, length(v))
+    for (i, x) in enumerate(v)
+        if !ismissing(x)
+            data[i] = x
+        end
+    end
+
+    data
+end
+
+function columnvalidity(v::AbstractVector)
+    Missing <: eltype(v) || return nothing
+    Bool[!ismissing(x) for x in v]
+end
+
+function addrustfinalizer(v, callback::Ptr{Cvoid}, data::Ptr{Cvoid})
+    finalizer(v) do _
+        ccall(callback, Cvoid, (Ptr{Cvoid},), data)
+    end
+    nothing
+end
+
+# Values that are rooted by a PersistentValue or GcPreserve, keyed by its id.
+const persistent = Dict{UInt64,Any}()
+
+function persist(id::UInt64, value)
+    persistent[id] = value
+    nothing
+end
+
+function persist_all(id::UInt64, values...)
+    persistent[id] = values
+    nothing
+end
+
+function unpersist(id::UInt64)
+    delete!(persistent, id)
+    nothing
+end
+
+npersistent() = length(persistent)
+
 end
@@ -23,6 +23,9 @@
 //!
 //! # Generating the bindings
 //!
+//! jlrs requires Rust 1.63 or newer. The `arrow` feature also requires the minimum Rust version
+//! of the `arrow` crate.
+//!
 //! This crate depends on `jl-sys` which contains the raw bindings to the Julia C API, these are
 //! generated by `bindgen`. You can find the requirements for using `bindgen` in [their User Guide].
 //!
@@ -59,6 +62,22 @@
 //! toolchain for a project that depends on `jl-sys` by calling the command
 //! `rustup override set stable-gnu` in the project root folder.
 //!
+//! #### Loading Julia at runtime
+//!
+//! By default `libjulia` is linked when your crate is built. If the `dynamic` feature flag is
+//! enabled, the library is loaded when Julia is initialized instead, which lets you use the same
+//! binary with different installations of Julia. The library is found by checking the
+//! `JULIA_DIR` environment variable at runtime, or by looking for the `julia` executable in the
+//! directories in `PATH`. You can also load it from a specific path by calling
+//! [`load_libjulia`] before initializing Julia. The headers are still used to generate the
+//! bindings if they can be found.
+//!
+//! #### Julia versions
+//!
+//! jlrs supports Julia 1.5. Because the C API of Julia is not stable across versions,
+//! initializing Julia fails if the version of `libjulia` that is used at runtime is a different
+//! version. You can check this version with [`version`].
+//!
 //!
 //! # Using this crate
 //!
@@ -271,7 +290,7 @@
 //! two ways, either as a task or as a thread. The first type should be used if you want to
 //! integrate the async runtime into a larger project that uses `async_std`. In order for the
 //! runtime to work correctly the `JULIA_NUM_THREADS` environment variable must be set to a value
-//! larger than 1.
+//! larger than the `n_threads` argument of the runtime.
 //!
 //! In order to call Julia with the async runtime you must implement the [`JuliaTask`] trait. The
 //! `run`-method of this trait is similar to the closures that are used in the examples
@@ -291,6 +310,38 @@
 //! You can find fully commented basic examples in [the examples directory of the repo].
 //!
 //!
+//! ## Multithreading
+//!
+//! Julia can only be called from the thread that initialized it and threads that have been
+//! started by Julia. Julia 1.9 is the first version that can adopt threads that have been
+//! started by other languages, this isn't possible with the versions supported by jlrs. For this
+//! reason [`Julia`] can't be sent to or shared with other threads, and initializing Julia again
+//! from another thread returns `JlrsError::ForeignThread`. Some types like [`PersistentValue`]
+//! can be sent to other threads, but using their values on a thread that can't call into Julia
+//! panics.
+//!
+//! If you want to use multiple threads, you can start Julia with multiple threads by setting
+//! the `JULIA_NUM_THREADS` environment variable and parallelize your code in Julia, for example
+//! with `Threads.@threads`. The async runtime lets other threads send tasks to Julia, and
+//! [`Value::call_async`] runs a function on another thread in Julia.
+//!
+//!
+//! ## Apache Arrow
+//!
+//! Tabular data can be shared between arrow-rs and Julia without serializing it by enabling the
+//! `arrow` feature flag. The [`arrow`] module lets you borrow a `RecordBatch` as a `NamedTuple`
+//! of vectors, which can be used as a table by `Tables.jl`, and convert a table from Julia back
+//! to a `RecordBatch`.
+//!
+//!
+//! ## Debugging the GC stack
+//!
+//! If you enable the `debug-stack` feature, jlrs records where frames and slots on the GC stack
+//! have been allocated and how many slots have been in use at most. The [`debug_stack`] module
+//! provides reports of this information, which can help you choose the size of the stack and
+//! find out why you've run out of slots.
+//!
+//!
 //! # Custom types
 //!
 //! In order to map a struct in Rust to one in Julia you can derive [`JuliaStruct`]. This will
@@ -356,14 +407,24 @@
 //! [`Value`]: value/struct.Value.html
 //! [`Value::new`]: value/struct.Value.html#method.new
 //! [`Value::call_async`]: value/struct.Value.html#method.call_async
+//! [`PersistentValue`]: persistent/struct.PersistentValue.html
 //! [`Value::cast`]: value/struct.Value.html#method.cast
 //! [`AsyncJulia`]: multitask/struct.AsyncJulia.html
+//! [`arrow`]: arrow/index.html
+//! [`debug_stack`]: debug_stack/index.html
+//! [`load_libjulia`]: fn.load_libjulia.html
+//! [`version`]: fn.version.html
 //! [the instructions for compiling Julia on Windows using Cygwin and MinGW]: https://github.com/JuliaLang/julia/blob/v1.5.2/doc/build/windows.md#cygwin-to-mingw-cross-compiling
 //! [the examples directory of the repo]: https://github.com/Taaitaaiger/jlrs/tree/v0.8/examples
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "debug-stack")]
+pub mod debug_stack;
 pub mod error;
 pub mod frame;
 pub mod global;
+pub mod info;
 #[doc(hidden)]
 pub mod jl_sys_export;
 #[cfg(all(feature = "async", target_os = "linux"))]
@@ -371,7 +432,9 @@ pub mod julia_future;
 pub mod mode;
 #[cfg(all(feature = "async", target_os = "linux"))]
 pub mod multitask;
+pub mod persistent;
 pub mod prelude;
+pub mod reload;
 mod stack;
 pub mod traits;
 #[doc(hidden)]
@@ -381,9 +444,14 @@ pub mod value;
 use error::{JlrsError, JlrsResult};
 use frame::{DynamicFrame, NullFrame, StaticFrame};
 use global::Global;
-use jl_sys::{jl_atexit_hook, jl_init, jl_init_with_image__threading, jl_is_initialized};
+use info::JuliaInfo;
+use jl_sys::{
+    jl_atexit_hook, jl_init, jl_init_with_image__threading, jl_is_initialized, jl_ver_major,
+    jl_ver_minor, jl_ver_patch,
+};
 use mode::Sync;
 use stack::{Dynamic, RawStack, StackView, Static};
+use std::cell::Cell;
 use std::ffi::{c_void, CString};
 use std::io::{Error as IOError, ErrorKind};
 use std::mem::MaybeUninit;
@@ -396,8 +464,106 @@ use value::Value;
 
 pub(crate) static INIT: AtomicBool = AtomicBool::new(false);
 
+thread_local! {
+    // Only the thread that has initialized Julia can call into Julia.
+    pub(crate) static JULIA_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
 pub(crate) static JLRS_JL: &'static str = include_str!("jlrs.jl");
 
+/// Load `libjulia` from `path`, this is only available if the `dynamic` feature is enabled. If
+/// this function isn't called, the library is found and loaded when Julia is initialized. Returns
+/// an error if the library has already been loaded, if it can't be opened, or if it doesn't
+/// export all functions and globals that jlrs uses.
+///
+/// This function is unsafe because loading a library executes its initialization routines.
+#[cfg(feature = "dynamic")]
+pub unsafe fn load_libjulia<P: AsRef<Path>>(path: P) -> JlrsResult<()> {
+    jl_sys::dynamic::load_libjulia(path).map_err(|e| JlrsError::other(e).into())
+}
+
+#[cfg(feature = "dynamic")]
+pub(crate) unsafe fn ensure_libjulia_loaded() -> JlrsResult<()> {
+    if jl_sys::dynamic::is_loaded() {
+        return Ok(());
+    }
+
+    jl_sys::dynamic::load_default().map_err(|e| JlrsError::other(e).into())
+}
+
+/// The version of Julia jlrs supports. Initializing Julia fails if another version of `libjulia`
+/// is used at runtime.
+pub const JULIA_VERSION: &str = "1.5";
+
+/// A version of Julia.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JuliaVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl std::fmt::Display for JuliaVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Returns the version of `libjulia` that is used at runtime. This can be called before Julia has
+/// been initialized. If the `dynamic` feature is enabled an error is returned if the library
+/// hasn't been loaded yet.
+pub fn version() -> JlrsResult<JuliaVersion> {
+    #[cfg(feature = "dynamic")]
+    {
+        if !jl_sys::dynamic::is_loaded() {
+            Err(JlrsError::LibJuliaNotLoaded)?;
+        }
+    }
+
+    unsafe {
+        Ok(JuliaVersion {
+            major: jl_ver_major() as u32,
+            minor: jl_ver_minor() as u32,
+            patch: jl_ver_patch() as u32,
+        })
+    }
+}
+
+// Panics if the current thread can't call into Julia, Global and frames must only be created on
+// a thread that can.
+#[track_caller]
+pub(crate) fn assert_julia_thread() {
+    if !JULIA_THREAD.with(|t| t.get()) {
+        panic!("{}", JlrsError::ForeignThread(JULIA_VERSION.into()));
+    }
+}
+
+// The error that is returned if Julia is initialized when it has already been initialized.
+pub(crate) fn already_initialized() -> Box<JlrsError> {
+    if JULIA_THREAD.with(|t| t.get()) {
+        Box::new(JlrsError::AlreadyInitialized)
+    } else {
+        Box::new(JlrsError::ForeignThread(JULIA_VERSION.into()))
+    }
+}
+
+// Julia's C API is only compatible with the version jlrs supports, so jlrs refuses to start if
+// a different version of libjulia is used.
+pub(crate) unsafe fn check_version() -> JlrsResult<()> {
+    #[cfg(feature = "dynamic")]
+    ensure_libjulia_loaded()?;
+
+    let version = version()?;
+    if format!("{}.{}", version.major, version.minor) != JULIA_VERSION {
+        Err(JlrsError::IncompatibleVersion(
+            JULIA_VERSION.into(),
+            version.to_string(),
+        ))?;
+    }
+
+    Ok(())
+}
+
 /// This struct can be created only once during the lifetime of your program. You must create it
 /// with [`Julia::init`] or [`Julia::init_with_image`] before you can do anything related to
 /// Julia. While this struct exists, Julia is active; dropping it causes the shutdown code to be
@@ -427,11 +593,14 @@ impl Julia {
     /// [`StaticFrame`]: frame/struct.StaticFrame.html
     /// [`DynamicFrame`]: frame/struct.DynamicFrame.html
     pub unsafe fn init(stack_size: usize) -> JlrsResult<Self> {
+        check_version()?;
+
         if jl_is_initialized() != 0 || INIT.swap(true, Ordering::SeqCst) {
-            return Err(JlrsError::AlreadyInitialized.into());
+            return Err(already_initialized());
         }
 
         jl_init();
+        JULIA_THREAD.with(|t| t.set(true));
         let mut jl = Julia {
             stack: RawStack::new(stack_size),
         };
@@ -472,8 +641,10 @@ impl Julia {
         julia_bindir: P,
         image_path: P,
     ) -> JlrsResult<Self> {
+        check_version()?;
+
         if INIT.swap(true, Ordering::SeqCst) {
-            Err(JlrsError::AlreadyInitialized)?;
+            return Err(already_initialized());
         }
 
         let julia_bindir_str = julia_bindir.as_ref().to_string_lossy().to_string();
@@ -493,6 +664,7 @@ impl Julia {
         let im_rel_path = CString::new(image_path_str).unwrap();
 
         jl_init_with_image__threading(bindir.as_ptr(), im_rel_path.as_ptr());
+        JULIA_THREAD.with(|t| t.set(true));
 
         let mut jl = Julia {
             stack: RawStack::new(stack_size),
@@ -524,8 +696,30 @@ impl Julia {
         self.stack.size()
     }
 
+    /// Returns information about the running instance of Julia, including its version, the
+    /// number of threads it can use and the options it has been started with.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use jlrs::prelude::*;
+    /// # use jlrs::util::JULIA;
+    /// # fn main() {
+    /// # JULIA.with(|j| {
+    /// # let mut julia = j.borrow_mut();
+    /// let info = julia.info().unwrap();
+    /// println!("Julia {} with {} threads", info.version, info.n_threads);
+    /// # });
+    /// # }
+    /// ```
+    pub fn info(&mut self) -> JlrsResult<JuliaInfo> {
+        self.dynamic_frame(JuliaInfo::new)
+    }
+
     /// Calls `include` in the `Main` module in Julia, which executes the file's contents in that
-    /// module. This has the same effect as calling `include` in the Julia REPL.
+    /// module. This has the same effect as calling `include` in the Julia REPL. You can use
+    /// [`Module::include`] to include a file in another module. If the file can change while
+    /// your application is running, a [`Reloader`] can be used to include and reload it.
     ///
     /// Example:
     ///
@@ -536,6 +730,9 @@ impl Julia {
     /// julia.include("MyJuliaCode.jl").unwrap();
     /// # }
     /// ```
+    ///
+    /// [`Module::include`]: value/module/struct.Module.html#method.include
+    /// [`Reloader`]: reload/struct.Reloader.html
     pub fn include<P: AsRef<Path>>(&mut self, path: P) -> JlrsResult<()> {
         if path.as_ref().exists() {
             return self.frame(3, |global, frame| {
@@ -584,6 +781,7 @@ impl Julia {
     ///
     /// [`StaticFrame`]: ../frame/struct.StaticFrame.html
     /// [`Value`]: ../value/struct.Value.html
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn frame<'base, 'julia: 'base, T, F>(
         &'julia mut self,
         capacity: usize,
@@ -627,6 +825,7 @@ impl Julia {
     ///
     /// [`DynamicFrame`]: ../frame/struct.DynamicFrame.html
     /// [`Value`]: ../value/struct.Value.html
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn dynamic_frame<'base, 'julia: 'base, T, F>(&'julia mut self, func: F) -> JlrsResult<T>
     where
         F: FnOnce(Global<'base>, &mut DynamicFrame<'base, Sync>) -> JlrsResult<T>,
@@ -678,6 +877,8 @@ impl CCall {
     ///
     /// [`Julia::init`]: struct.Julia.html#method.init
     pub unsafe fn new(stack_size: usize) -> Self {
+        // Functions called through `ccall` run on a thread that is known to Julia.
+        JULIA_THREAD.with(|t| t.set(true));
         CCall {
             stack: None,
             stack_size,
@@ -716,6 +917,7 @@ impl CCall {
     ///
     /// [`StaticFrame`]: ../frame/struct.StaticFrame.html
     /// [`Value`]: ../value/struct.Value.html
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn frame<'base, 'julia: 'base, T, F>(
         &'julia mut self,
         capacity: usize,
@@ -747,6 +949,7 @@ impl CCall {
     ///
     /// [`DynamicFrame`]: ../frame/struct.DynamicFrame.html
     /// [`Value`]: ../value/struct.Value.html
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn dynamic_frame<'base, 'julia: 'base, T, F>(&'julia mut self, func: F) -> JlrsResult<T>
     where
         F: FnOnce(Global<'base>, &mut DynamicFrame<'base, Sync>) -> JlrsResult<T>,
@@ -20,7 +20,7 @@ use crate::stack::{Dynamic, StackView};
 use crate::traits::multitask::{JuliaTask, ReturnChannel};
 use crate::value::module::Module;
 use crate::value::Value;
-use crate::{INIT, JLRS_JL};
+use crate::{INIT, JLRS_JL, JULIA_THREAD};
 use async_std::channel::{
     bounded, Receiver as AsyncStdReceiver, RecvError, Sender as AsyncStdSender, TrySendError,
 };
@@ -30,6 +30,7 @@ use async_std::task::{self, JoinHandle as AsyncStdHandle};
 use jl_sys::{jl_atexit_hook, jl_gc_safepoint, jl_init_with_image__threading, jl_is_initialized};
 use std::ffi::{c_void, CString};
 use std::io::{Error as IOError, ErrorKind};
+use std::panic;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Condvar, Mutex};
@@ -50,7 +51,7 @@ use std::time::Duration;
 ///  - `channel_capacity`: the capacity of the channel used to communicate with the runtime.
 ///  - `n_threads`: the number of threads that can be used to run tasks at the same time, it must
 ///    be less than the number of threads set with the `JULIA_NUM_THREADS` environment variable
-///    (which defaults to 1).
+///    (which defaults to 1). If it isn't, `JlrsError::TooManyThreads` is returned.
 ///  - `stack_size`: the size of a stack that is created for each of the tasks threads and the
 ///    main thread (so `n_thread + 1` stacks with `stack_size` slots are created).
 ///  - `process_events_ms`: to ensure the garbage collector can run and tasks that have yielded in
@@ -88,8 +89,21 @@ where
     ) -> JlrsResult<(Self, ThreadHandle<JlrsResult<()>>)> {
         let (sender, receiver) = bounded(channel_capacity);
         let julia = AsyncJulia { sender };
-        let handle =
-            thread::spawn(move || run_async(n_threads, stack_size, process_events_ms, receiver));
+        let (started_sender, started) = bounded(1);
+        let handle = thread::spawn(move || {
+            run_async(
+                n_threads,
+                stack_size,
+                process_events_ms,
+                receiver,
+                started_sender,
+            )
+        });
+
+        if task::block_on(started.recv()).is_err() {
+            return Err(startup_error(handle.join()));
+        }
+
         julia.try_set_wake_fn().map_err(other_err)?;
 
         Ok((julia, handle))
@@ -110,9 +124,21 @@ where
     ) -> JlrsResult<(Self, AsyncStdHandle<JlrsResult<()>>)> {
         let (sender, receiver) = bounded(channel_capacity);
         let julia = AsyncJulia { sender };
+        let (started_sender, started) = bounded(1);
         let handle = task::spawn_blocking(move || {
-            run_async(n_threads, stack_size, process_events_ms, receiver)
+            run_async(
+                n_threads,
+                stack_size,
+                process_events_ms,
+                receiver,
+                started_sender,
+            )
         });
+
+        if started.recv().await.is_err() {
+            return Err(startup_error(Ok(handle.await)));
+        }
+
         julia.set_wake_fn().await?;
 
         Ok((julia, handle))
@@ -149,16 +175,23 @@ where
     {
         let (sender, receiver) = bounded(channel_capacity);
         let julia = AsyncJulia { sender };
+        let (started_sender, started) = bounded(1);
         let handle = thread::spawn(move || {
             run_async_with_image(
                 n_threads,
                 stack_size,
                 process_events_ms,
                 receiver,
+                started_sender,
                 julia_bindir,
                 image_path,
             )
         });
+
+        if task::block_on(started.recv()).is_err() {
+            return Err(startup_error(handle.join()));
+        }
+
         julia.try_set_wake_fn().map_err(other_err)?;
 
         Ok((julia, handle))
@@ -195,16 +228,23 @@ where
     {
         let (sender, receiver) = bounded(channel_capacity);
         let julia = AsyncJulia { sender };
+        let (started_sender, started) = bounded(1);
         let handle = task::spawn_blocking(move || {
             run_async_with_image(
                 n_threads,
                 stack_size,
                 process_events_ms,
                 receiver,
+                started_sender,
                 julia_bindir,
                 image_path,
             )
         });
+
+        if started.recv().await.is_err() {
+            return Err(startup_error(Ok(handle.await)));
+        }
+
         julia.set_wake_fn().await?;
 
         Ok((julia, handle))
@@ -445,6 +485,7 @@ fn run_async<T, R>(
     stack_size: usize,
     process_events_ms: u64,
     receiver: AsyncStdReceiver<Message<T, R>>,
+    started: AsyncStdSender<()>,
 ) -> JlrsResult<()>
 where
     T: Send + Sync + 'static,
@@ -452,16 +493,21 @@ where
 {
     task::block_on(async {
         let mut mt_stack: MultitaskStack<T, R> = unsafe {
+            crate::check_version()?;
+
             if jl_is_initialized() != 0 || INIT.swap(true, Ordering::SeqCst) {
-                return Err(JlrsError::AlreadyInitialized.into());
+                return Err(crate::already_initialized());
             }
 
             jl_sys::jl_init();
+            JULIA_THREAD.with(|t| t.set(true));
             let jlrs_jl = CString::new(JLRS_JL).expect("Invalid Jlrs module");
             jl_sys::jl_eval_string(jlrs_jl.as_ptr());
+            check_threads(n_threads)?;
 
             MultitaskStack::new(n_threads, stack_size)
         };
+        started.send(()).await.expect("Channel was closed");
 
         loop {
             match timeout(Duration::from_millis(process_events_ms), receiver.recv()).await {
@@ -528,6 +574,7 @@ fn run_async_with_image<T, R, P, Q>(
     stack_size: usize,
     process_events_ms: u64,
     receiver: AsyncStdReceiver<Message<T, R>>,
+    started: AsyncStdSender<()>,
     julia_bindir: P,
     image_path: Q,
 ) -> JlrsResult<()>
@@ -539,8 +586,10 @@ where
 {
     task::block_on(async {
         let mut mt_stack: MultitaskStack<T, R> = unsafe {
+            crate::check_version()?;
+
             if jl_is_initialized() != 0 || INIT.swap(true, Ordering::SeqCst) {
-                return Err(JlrsError::AlreadyInitialized.into());
+                return Err(crate::already_initialized());
             }
 
             let julia_bindir_str = julia_bindir.as_ref().to_string_lossy().to_string();
@@ -560,11 +609,15 @@ where
             let im_rel_path = std::ffi::CString::new(image_path_str).unwrap();
 
             jl_init_with_image__threading(bindir.as_ptr(), im_rel_path.as_ptr());
+            JULIA_THREAD.with(|t| t.set(true));
 
             let jlrs_jl = CString::new(JLRS_JL).expect("Invalid Jlrs module");
             jl_sys::jl_eval_string(jlrs_jl.as_ptr());
+            check_threads(n_threads)?;
+
             MultitaskStack::new(n_threads, stack_size)
         };
+        started.send(()).await.expect("Channel was closed");
 
         loop {
             match timeout(Duration::from_millis(process_events_ms), receiver.recv()).await {
@@ -625,6 +678,47 @@ where
     })
 }
 
+// Tasks are spawned on one of Julia's threads with `Threads.@spawn`. If Julia doesn't have more
+// threads than the number of tasks that can run at the same time, these tasks are executed
+// sequentially.
+unsafe fn check_threads(n_threads: usize) -> JlrsResult<()> {
+    let res = julia_threads().and_then(|n_julia_threads| {
+        if n_threads >= n_julia_threads {
+            Err(JlrsError::TooManyThreads(n_threads, n_julia_threads))?;
+        }
+
+        Ok(())
+    });
+
+    // Julia can't be used if the runtime doesn't start.
+    if res.is_err() {
+        jl_atexit_hook(0);
+    }
+
+    res
+}
+
+unsafe fn julia_threads() -> JlrsResult<usize> {
+    let cmd = CString::new("Base.Threads.nthreads()").unwrap();
+    let n_julia_threads = jl_sys::jl_eval_string(cmd.as_ptr());
+
+    if n_julia_threads.is_null() {
+        let exc = Value::wrap(jl_sys::jl_exception_occurred());
+        Err(JlrsError::Exception(exc.type_name().into()))?;
+    }
+
+    Ok(Value::wrap(n_julia_threads).cast::<isize>()? as usize)
+}
+
+// If the runtime fails to start the thread or task it runs on returns the error.
+fn startup_error(result: thread::Result<JlrsResult<()>>) -> Box<JlrsError> {
+    match result {
+        Ok(Err(e)) => e,
+        Ok(Ok(())) => unreachable!(),
+        Err(e) => panic::resume_unwind(e),
+    }
+}
+
 fn call_set_wake_fn(stack: &mut [*mut c_void]) -> JlrsResult<()> {
     unsafe {
         let global = Global::new();
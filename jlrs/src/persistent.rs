@@ -0,0 +1,381 @@
+//! Root Julia values independently of frames.
+//!
+//! A [`Value`] can only be used while the frame that roots it is active. If a value must be
+//! used across several calls to [`Julia::frame`] or by multiple async tasks, like a loaded model
+//! or a lookup table, it can be stored in a [`PersistentValue`]. A `PersistentValue` roots its
+//! value in a global root set rather than in a frame, so it can be stored in Rust structs and
+//! used in any frame until it's dropped:
+//!
+//! ```no_run
+//! # use jlrs::prelude::*;
+//! # use jlrs::persistent::PersistentValue;
+//! # fn main() {
+//! # let mut julia = unsafe { Julia::init(16).unwrap() };
+//! let table = julia.frame(2, |_global, frame| {
+//!     let table = Value::eval_string(frame, "Dict(1 => 2, 3 => 4)")?.unwrap();
+//!     PersistentValue::new(frame, table)
+//! }).unwrap();
+//!
+//! julia.frame(3, |global, frame| {
+//!     let key = Value::new(frame, 3)?;
+//!     let value = Module::base(global)
+//!         .function("getindex")?
+//!         .call2(frame, table.value(), key)?
+//!         .unwrap();
+//!
+//!     assert_eq!(value.cast::<i64>()?, 4);
+//!     Ok(())
+//! }).unwrap();
+//! # }
+//! ```
+//!
+//! Dropping a `PersistentValue` is thread-safe and doesn't call into Julia. Instead, its value is
+//! unrooted the next time a `PersistentValue` is created or when
+//! [`PersistentValue::release_dropped`] is called.
+//!
+//! If native code that runs outside a frame needs the data of an array, e.g. because it's
+//! processed in parallel by other threads, the array must stay alive while that code runs. A
+//! [`GcPreserve`] keeps a set of values rooted until it's dropped without borrowing the frame,
+//! similar to Julia's `GC.@preserve`. It gives access to the data of preserved arrays as an
+//! [`ArrayView`] or [`ArrayViewMut`], which can be sent to other threads:
+//!
+//! ```no_run
+//! # use jlrs::prelude::*;
+//! # fn main() {
+//! # let mut julia = unsafe { Julia::init(16).unwrap() };
+//! julia.frame(2, |_global, frame| {
+//!     let array = Value::new_array::<f64, _, _>(frame, 1024)?;
+//!     let mut preserved = array.preserve(frame)?;
+//!     let mut view = unsafe { preserved.inline_data_mut::<f64>(0)? };
+//!
+//!     std::thread::scope(|scope| {
+//!         scope.spawn(|| {
+//!             for (i, x) in view.iter_mut().enumerate() {
+//!                 *x = i as f64;
+//!             }
+//!         });
+//!
+//!         // The frame can still be used while the other thread runs.
+//!         Value::new(frame, 1usize).map(|_| ())
+//!     })
+//! }).unwrap();
+//! # }
+//! ```
+//!
+//! [`Value`]: ../value/struct.Value.html
+//! [`Julia::frame`]: ../struct.Julia.html#method.frame
+//! [`PersistentValue`]: struct.PersistentValue.html
+//! [`PersistentValue::release_dropped`]: struct.PersistentValue.html#method.release_dropped
+//! [`GcPreserve`]: struct.GcPreserve.html
+//! [`ArrayView`]: ../value/array/struct.ArrayView.html
+//! [`ArrayViewMut`]: ../value/array/struct.ArrayViewMut.html
+
+use crate::error::{exception, JlrsError, JlrsResult};
+use crate::global::Global;
+use crate::traits::{private::Internal, Cast, Frame, ValidLayout};
+use crate::value::array::{Array, ArrayView, ArrayViewMut, Dimensions};
+use crate::value::module::Module;
+use crate::value::Value;
+use crate::JULIA_THREAD;
+use jl_sys::{jl_array_data, jl_box_uint64, jl_call1, jl_value_t};
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static DROPPED: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+/// A Julia value that is rooted independently of any frame. It remains rooted until it's
+/// dropped.
+pub struct PersistentValue {
+    ptr: *mut jl_value_t,
+    id: u64,
+}
+
+// Safety: the value can only be used from a thread that can call into Julia because a frame is
+// required to do so, and dropping a `PersistentValue` doesn't call into Julia.
+unsafe impl Send for PersistentValue {}
+unsafe impl Sync for PersistentValue {}
+
+impl PersistentValue {
+    /// Root `value` until the returned `PersistentValue` is dropped. The values of
+    /// `PersistentValue`s that have been dropped are unrooted first. This temporarily takes two
+    /// slots on the GC stack.
+    pub fn new<'frame, F>(frame: &mut F, value: Value<'_, 'static>) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+    {
+        Self::release_dropped(frame)?;
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        call_jlrs(frame, "persist", id, &[value])?;
+
+        unsafe {
+            Ok(PersistentValue {
+                ptr: value.ptr(),
+                id,
+            })
+        }
+    }
+
+    /// Unroot the values of all `PersistentValue`s that have been dropped. This temporarily
+    /// takes two slots on the GC stack. If an error occurs, the values that haven't been
+    /// unrooted yet are released by the next call to this method.
+    pub fn release_dropped<'frame, F>(frame: &mut F) -> JlrsResult<()>
+    where
+        F: Frame<'frame>,
+    {
+        let mut dropped = std::mem::take(&mut *DROPPED.lock().expect("Poisoned lock"));
+        while let Some(id) = dropped.pop() {
+            if let Err(e) = call_jlrs(frame, "unpersist", id, &[]) {
+                dropped.push(id);
+                DROPPED.lock().expect("Poisoned lock").append(&mut dropped);
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the rooted value. It can be used until this `PersistentValue` is dropped.
+    ///
+    /// A `PersistentValue` can be sent to other threads, but its value can only be used on a
+    /// thread that can call into Julia. This method panics if it's called on another thread.
+    #[track_caller]
+    pub fn value(&self) -> Value<'_, 'static> {
+        crate::assert_julia_thread();
+        unsafe { Value::wrap(self.ptr) }
+    }
+
+    /// Root the value in `frame` and return it. The returned value can be used until the frame
+    /// goes out of scope, even if this `PersistentValue` is dropped. This takes one slot on the
+    /// GC stack.
+    pub fn root<'frame, F>(&self, frame: &mut F) -> JlrsResult<Value<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+    {
+        unsafe { frame.protect(self.ptr, Internal).map_err(Into::into) }
+    }
+
+    /// Cast the rooted value to `T`, see [`Value::cast`] for more information.
+    ///
+    /// [`Value::cast`]: ../value/struct.Value.html#method.cast
+    pub fn cast<'a, T>(&'a self) -> JlrsResult<<T as Cast<'a, 'static>>::Output>
+    where
+        T: Cast<'a, 'static>,
+    {
+        self.value().cast::<T>()
+    }
+}
+
+impl Drop for PersistentValue {
+    fn drop(&mut self) {
+        if let Ok(mut dropped) = DROPPED.lock() {
+            dropped.push(self.id);
+        }
+    }
+}
+
+impl Debug for PersistentValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("PersistentValue")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+/// A set of values that are rooted until this guard is dropped, the equivalent of Julia's
+/// `GC.@preserve`. A `GcPreserve` doesn't borrow the frame it has been created with, so the frame
+/// can still be used while the values are preserved. It's created by calling [`GcPreserve::new`]
+/// or [`Value::preserve`].
+///
+/// If the guard is dropped on a thread that can call into Julia, the values are unrooted
+/// immediately, which calls into Julia. Otherwise, or if this fails, they're unrooted like the
+/// value of a dropped [`PersistentValue`]: the next time a `PersistentValue` or `GcPreserve` is
+/// created or when [`PersistentValue::release_dropped`] is called.
+///
+/// [`GcPreserve::new`]: struct.GcPreserve.html#method.new
+/// [`Value::preserve`]: ../value/struct.Value.html#method.preserve
+/// [`PersistentValue`]: struct.PersistentValue.html
+/// [`PersistentValue::release_dropped`]: struct.PersistentValue.html#method.release_dropped
+pub struct GcPreserve<'data> {
+    values: Vec<*mut jl_value_t>,
+    id: u64,
+    _data: PhantomData<&'data ()>,
+}
+
+// Safety: the same rules apply as for `PersistentValue`, dropping a `GcPreserve` only calls into
+// Julia on a thread that can do so.
+unsafe impl<'data> Send for GcPreserve<'data> {}
+unsafe impl<'data> Sync for GcPreserve<'data> {}
+
+impl<'data> GcPreserve<'data> {
+    /// Preserve `values` until the returned `GcPreserve` is dropped. The values of
+    /// `PersistentValue`s that have been dropped are unrooted first. All values are rooted
+    /// together, this temporarily takes two slots on the GC stack.
+    pub fn new<'frame, F>(frame: &mut F, values: &[Value<'_, 'data>]) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+    {
+        PersistentValue::release_dropped(frame)?;
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        call_jlrs(frame, "persist_all", id, values)?;
+
+        unsafe {
+            Ok(GcPreserve {
+                values: values.iter().map(|v| v.ptr()).collect(),
+                id,
+                _data: PhantomData,
+            })
+        }
+    }
+
+    /// Returns the number of preserved values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if no values are preserved.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the preserved value at index `idx`, or `None` if the index is out of bounds. Like
+    /// [`PersistentValue::value`], this method panics if it's called on a thread that can't call
+    /// into Julia.
+    ///
+    /// [`PersistentValue::value`]: struct.PersistentValue.html#method.value
+    #[track_caller]
+    pub fn value(&self, idx: usize) -> Option<Value<'_, 'data>> {
+        crate::assert_julia_thread();
+        self.values.get(idx).map(|&v| unsafe { Value::wrap(v) })
+    }
+
+    /// Immutably borrow the data of the inline array at index `idx` as an [`ArrayView`]. Returns
+    /// `JlrsError::OutOfBounds` if the index is out of bounds, `JlrsError::NotAnArray` if the
+    /// value isn't an array, `JlrsError::NotInline` if the data is not stored inline, or
+    /// `JlrsError::WrongType` if the type of the elements is incorrect.
+    ///
+    /// # Safety
+    ///
+    /// The frame isn't borrowed, so it's your responsibility to ensure Julia doesn't resize the
+    /// array or change its contents while the data is borrowed.
+    ///
+    /// [`ArrayView`]: ../value/array/struct.ArrayView.html
+    pub unsafe fn inline_data<T>(&self, idx: usize) -> JlrsResult<ArrayView<'_, T>>
+    where
+        T: ValidLayout,
+    {
+        let (data, dimensions) = self.array_data::<T>(idx)?;
+        let data = std::slice::from_raw_parts(data, dimensions.size());
+        Ok(ArrayView::new(data, &dimensions))
+    }
+
+    /// Mutably borrow the data of the inline array at index `idx` as an [`ArrayViewMut`].
+    /// Returns `JlrsError::OutOfBounds` if the index is out of bounds, `JlrsError::NotAnArray`
+    /// if the value isn't an array, `JlrsError::NotInline` if the data is not stored inline, or
+    /// `JlrsError::WrongType` if the type of the elements is incorrect.
+    ///
+    /// # Safety
+    ///
+    /// The frame isn't borrowed, so it's your responsibility to ensure Julia doesn't resize the
+    /// array or access its contents while the data is borrowed. The same array must not be
+    /// preserved by another `GcPreserve` that borrows its data.
+    ///
+    /// [`ArrayViewMut`]: ../value/array/struct.ArrayViewMut.html
+    pub unsafe fn inline_data_mut<T>(&mut self, idx: usize) -> JlrsResult<ArrayViewMut<'_, T>>
+    where
+        T: ValidLayout,
+    {
+        let (data, dimensions) = self.array_data::<T>(idx)?;
+        let data = std::slice::from_raw_parts_mut(data, dimensions.size());
+        Ok(ArrayViewMut::new(data, &dimensions))
+    }
+
+    unsafe fn array_data<T>(&self, idx: usize) -> JlrsResult<(*mut T, Dimensions)>
+    where
+        T: ValidLayout,
+    {
+        let array = match self.value(idx) {
+            Some(value) => value.cast::<Array>()?,
+            None => Err(JlrsError::OutOfBounds(idx, self.len()))?,
+        };
+
+        if !array.contains::<T>() {
+            Err(JlrsError::WrongType)?;
+        }
+
+        if !array.is_inline_array() {
+            Err(JlrsError::NotInline)?;
+        }
+
+        let data = jl_array_data(array.ptr().cast()).cast();
+        let dimensions = Dimensions::from_array(array.ptr().cast());
+        Ok((data, dimensions))
+    }
+}
+
+impl<'data> Drop for GcPreserve<'data> {
+    fn drop(&mut self) {
+        if JULIA_THREAD.with(|t| t.get()) && unsafe { unpersist_now(self.id) } {
+            return;
+        }
+
+        if let Ok(mut dropped) = DROPPED.lock() {
+            dropped.push(self.id);
+        }
+    }
+}
+
+impl<'data> Debug for GcPreserve<'data> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("GcPreserve")
+            .field("id", &self.id)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+fn call_jlrs<'frame, F>(
+    frame: &mut F,
+    func: &str,
+    id: u64,
+    values: &[Value<'_, '_>],
+) -> JlrsResult<()>
+where
+    F: Frame<'frame>,
+{
+    frame.frame(2, |frame| unsafe {
+        let id = Value::new(frame, id)?;
+        let func = Module::main(Global::new())
+            .submodule("Jlrs")?
+            .function(func)?;
+
+        let mut args = Vec::with_capacity(values.len() + 1);
+        args.push(id);
+        args.extend(values.iter().map(|v| Value::wrap(v.ptr())));
+        let res = func.call(frame, &mut args)?;
+
+        match res {
+            Ok(_) => Ok(()),
+            Err(e) => exception(e.type_name().into()),
+        }
+    })
+}
+
+// Unroot the values with this id without using a frame, this must only be called on a thread
+// that can call into Julia. Returns `false` if an exception is thrown.
+unsafe fn unpersist_now(id: u64) -> bool {
+    let func = match Module::main(Global::new())
+        .submodule("Jlrs")
+        .and_then(|jlrs| jlrs.function("unpersist"))
+    {
+        Ok(func) => func,
+        Err(_) => return false,
+    };
+
+    // The boxed id is rooted by `jl_call1`, nothing is allocated before that.
+    !jl_call1(func.ptr(), jl_box_uint64(id)).is_null()
+}
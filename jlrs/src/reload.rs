@@ -0,0 +1,251 @@
+//! Reload Julia files that have been changed.
+//!
+//! A [`Reloader`] includes files into fresh modules and keeps track of them. When a tracked file
+//! has been modified it can be reloaded, which includes it into a new module that replaces the
+//! old one. The names of functions that have been added, removed or changed are reported after
+//! a file has been reloaded.
+//!
+//! A [`Reloader`] only needs a frame to include and reload files, so it can be used both with
+//! [`Julia`] and from a task that runs on the async runtime:
+//!
+//! ```no_run
+//! # use jlrs::prelude::*;
+//! # use jlrs::reload::Reloader;
+//! # fn main() {
+//! # let mut julia = unsafe { Julia::init(16).unwrap() };
+//! let mut reloader = Reloader::new();
+//!
+//! julia.frame(0, |_global, frame| {
+//!     reloader.track(frame, "model.jl", "Model")
+//! }).unwrap();
+//!
+//! // ...
+//!
+//! julia.frame(0, |_global, frame| {
+//!     for report in reloader.reload(frame) {
+//!         match report {
+//!             Ok(report) => println!("{:?} changed: {:?}", report.path, report.changed),
+//!             Err(e) => println!("{}", e),
+//!         }
+//!     }
+//!     Ok(())
+//! }).unwrap();
+//! # }
+//! ```
+//!
+//! Modules that have been replaced are never freed, values that have been obtained from them
+//! remain valid.
+//!
+//! [`Reloader`]: struct.Reloader.html
+//! [`Julia`]: ../struct.Julia.html
+
+use crate::error::{JlrsError, JlrsResult};
+use crate::global::Global;
+use crate::traits::Frame;
+use crate::value::array::Array;
+use crate::value::module::Module;
+use crate::value::symbol::Symbol;
+use crate::value::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Keeps track of included files and reloads them when they have been modified.
+#[derive(Debug, Default)]
+pub struct Reloader {
+    files: Vec<TrackedFile>,
+}
+
+/// The result of reloading a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// The path of the file that has been reloaded.
+    pub path: PathBuf,
+    /// The names of the functions that have been added, removed or changed.
+    pub changed: Vec<String>,
+}
+
+#[derive(Debug)]
+struct TrackedFile {
+    path: PathBuf,
+    module: String,
+    modified: Option<SystemTime>,
+}
+
+impl Reloader {
+    /// Create a new `Reloader` that doesn't track any files.
+    pub fn new() -> Self {
+        Reloader::default()
+    }
+
+    /// Include the file at `path` into a new module named `module` and start tracking it. The
+    /// module is not bound to a name in `Main`, use [`Reloader::module`] to access it. If the
+    /// file is already tracked it's reloaded. Returns an error if the file doesn't exist or if
+    /// an exception is thrown while including it. This takes two slots on the GC stack.
+    ///
+    /// [`Reloader::module`]: struct.Reloader.html#method.module
+    pub fn track<'frame, F, P, N>(&mut self, frame: &mut F, path: P, module: N) -> JlrsResult<()>
+    where
+        F: Frame<'frame>,
+        P: AsRef<Path>,
+        N: AsRef<str>,
+    {
+        let path = canonicalize(path.as_ref())?;
+        let modified = modified(&path);
+
+        frame.frame(2, |frame| {
+            call_tracked(frame, "includetracked", &path, module.as_ref())?;
+            Ok(())
+        })?;
+
+        match self.files.iter_mut().find(|file| file.path == path) {
+            Some(file) => {
+                file.module = module.as_ref().into();
+                file.modified = modified;
+            }
+            None => self.files.push(TrackedFile {
+                path,
+                module: module.as_ref().into(),
+                modified,
+            }),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the module that contains the code of the tracked file at `path`. Returns an error
+    /// if this file is not tracked. This temporarily takes two slots on the GC stack.
+    pub fn module<'base, 'frame, F, P>(
+        &self,
+        _: Global<'base>,
+        frame: &mut F,
+        path: P,
+    ) -> JlrsResult<Module<'base>>
+    where
+        F: Frame<'frame>,
+        P: AsRef<Path>,
+    {
+        let file = self.find(path.as_ref())?;
+
+        frame.frame(2, |frame| unsafe {
+            let path = Value::new(frame, file.path.to_string_lossy())?;
+            let module = Module::main(Global::new())
+                .submodule("Jlrs")?
+                .function("trackedmodule")?
+                .call1(frame, path)?
+                .map_err(|e| JlrsError::Exception(e.type_name().into()))?;
+
+            // Tracked modules are never freed.
+            Ok(Module::wrap(module.cast::<Module>()?.ptr()))
+        })
+    }
+
+    /// Returns the paths of all tracked files.
+    pub fn tracked_files(&self) -> Vec<PathBuf> {
+        self.files.iter().map(|file| file.path.clone()).collect()
+    }
+
+    /// Returns the paths of the tracked files that have been modified since they were last
+    /// included. A file is considered modified if its modification time has changed.
+    pub fn modified_files(&self) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .filter(|file| modified(&file.path) != file.modified)
+            .map(|file| file.path.clone())
+            .collect()
+    }
+
+    /// Reload all tracked files that have been modified and return the result of reloading each
+    /// of them, see [`Reloader::reload_file`]. A file that can't be reloaded doesn't prevent the
+    /// other files from being reloaded. It keeps the module that contained its code before and
+    /// is still considered modified, so it's reloaded again the next time this method is called.
+    /// This temporarily takes two slots on the GC stack.
+    ///
+    /// [`Reloader::reload_file`]: struct.Reloader.html#method.reload_file
+    pub fn reload<'frame, F>(&mut self, frame: &mut F) -> Vec<JlrsResult<ReloadReport>>
+    where
+        F: Frame<'frame>,
+    {
+        self.modified_files()
+            .into_iter()
+            .map(|path| self.reload_file(frame, path))
+            .collect()
+    }
+
+    /// Reload the tracked file at `path`, whether it has been modified or not. Returns an error
+    /// if the file isn't tracked or if an exception is thrown while it's reloaded, in the latter
+    /// case the module that contained the code of this file before it was reloaded is kept.
+    /// This temporarily takes two slots on the GC stack.
+    pub fn reload_file<'frame, F, P>(&mut self, frame: &mut F, path: P) -> JlrsResult<ReloadReport>
+    where
+        F: Frame<'frame>,
+        P: AsRef<Path>,
+    {
+        let file = self.find(path.as_ref())?;
+        let path = file.path.clone();
+        let modified = modified(&path);
+
+        let changed = frame.frame(2, |frame| unsafe {
+            let changed = call_tracked(frame, "reloadtracked", &path, &file.module)?;
+
+            // Symbols are never freed by the garbage collector.
+            let changed = changed
+                .cast::<Array>()?
+                .value_data(frame)?
+                .into_slice()
+                .iter()
+                .map(|name| Symbol::wrap(name.ptr().cast()).as_string())
+                .collect();
+
+            Ok(changed)
+        })?;
+
+        self.files
+            .iter_mut()
+            .find(|file| file.path == path)
+            .expect("File is tracked")
+            .modified = modified;
+
+        Ok(ReloadReport { path, changed })
+    }
+
+    fn find(&self, path: &Path) -> JlrsResult<&TrackedFile> {
+        let path = canonicalize(path)?;
+        self.files
+            .iter()
+            .find(|file| file.path == path)
+            .ok_or_else(|| JlrsError::NotTracked(path.to_string_lossy().into()).into())
+    }
+}
+
+fn call_tracked<'frame, F>(
+    frame: &mut F,
+    func: &str,
+    path: &Path,
+    module: &str,
+) -> JlrsResult<Value<'frame, 'static>>
+where
+    F: Frame<'frame>,
+{
+    unsafe {
+        let global = Global::new();
+        let path_str = path.to_string_lossy();
+        let path_jl = Value::new(frame, path_str.as_ref())?;
+        let module: Value = Symbol::new(global, module).into();
+
+        Module::main(global)
+            .submodule("Jlrs")?
+            .function(func)?
+            .call2(frame, path_jl, module)?
+            .map_err(|e| JlrsError::IncludeError(path_str.into(), e.type_name().into()).into())
+    }
+}
+
+fn canonicalize(path: &Path) -> JlrsResult<PathBuf> {
+    path.canonicalize()
+        .map_err(|_| JlrsError::IncludeNotFound(path.to_string_lossy().into()).into())
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
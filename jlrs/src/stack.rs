@@ -65,8 +65,16 @@ impl<'stack, M: Mode, V> StackView<'stack, M, V> {
         println!("{:?}", &self.stack);
     }
 
+    #[cfg(feature = "debug-stack")]
+    pub(crate) fn report(&self) -> Option<crate::debug_stack::StackReport> {
+        crate::debug_stack::stack_report(self.stack)
+    }
+
     pub(crate) unsafe fn pop_frame(&mut self, idx: FrameIdx) {
-        M::pop_frame(&mut self.stack, idx)
+        M::pop_frame(self.stack, idx);
+
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::pop_frame(self.stack, idx.0);
     }
 
     pub(crate) unsafe fn nest_static<'nested>(&'nested mut self) -> StackView<'nested, M, Static> {
@@ -110,6 +118,7 @@ where
         }
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub(crate) unsafe fn new_frame(&mut self) -> JlrsResult<FrameIdx> {
         if self.size() + 2 >= self.stack.len() {
             return Err(Box::new(
@@ -118,7 +127,24 @@ where
         }
 
         let size = self.size();
-        Ok(M::new_dynamic_frame(&mut self.stack, size))
+        let idx = M::new_dynamic_frame(self.stack, size);
+
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::push_frame(self.stack, idx.0, None);
+
+        Ok(idx)
+    }
+
+    #[cfg_attr(feature = "debug-stack", track_caller)]
+    // Returns an error if `n` outputs can't be created.
+    pub(crate) fn check_outputs(&self, n: usize) -> JlrsResult<()> {
+        if self.size() + n > self.stack.len() {
+            return Err(Box::new(
+                AllocError::StackOverflow(n, self.stack.len()).into(),
+            ));
+        }
+
+        Ok(())
     }
 
     pub(crate) unsafe fn new_output<'output>(
@@ -135,9 +161,14 @@ where
         self.stack[sz] = null_mut();
         self.stack[idx.0 - 2] = (self.stack[idx.0 - 2] as usize + 2) as _;
         self.stack[0] = (self.size() + 1) as _;
+
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::push_slot(self.stack, idx.0);
+
         Ok(Output::new(sz))
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub(crate) unsafe fn protect<'output>(
         &mut self,
         idx: FrameIdx,
@@ -151,9 +182,26 @@ where
         self.stack[idx.0 - 2] = (self.stack[idx.0 - 2] as usize + 2) as _;
         self.stack[0] = (self.size() + 1) as _;
 
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::push_slot(self.stack, idx.0);
+
         Ok(Value::wrap(value.cast::<_>()))
     }
 
+    // Remove all slots of the frame starting at `len`, this frame must be the last frame.
+    pub(crate) unsafe fn truncate(&mut self, idx: FrameIdx, len: usize) {
+        let size = self.size();
+        for slot in &mut self.stack[idx.0 + len..size] {
+            *slot = null_mut();
+        }
+
+        self.stack[idx.0 - 2] = (len << 1) as _;
+        self.stack[0] = (idx.0 + len) as _;
+
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::clear_slots(self.stack, idx.0, len);
+    }
+
     pub(crate) unsafe fn protect_output<'output>(
         &mut self,
         output: Output,
@@ -176,6 +224,7 @@ where
         }
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub(crate) unsafe fn new_frame(&mut self, capacity: usize) -> JlrsResult<FrameIdx> {
         let size = self.size();
         if size + capacity + 2 >= self.stack.len() {
@@ -184,17 +233,37 @@ where
             ));
         }
 
-        Ok(M::new_frame(&mut self.stack, size, capacity))
+        let idx = M::new_frame(self.stack, size, capacity);
+
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::push_frame(self.stack, idx.0, Some(capacity));
+
+        Ok(idx)
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub(crate) unsafe fn new_output<'output>(
         &mut self,
         idx: FrameIdx,
         offset: usize,
     ) -> Output<'output> {
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::set_slot(self.stack, idx.0, offset);
+
         Output::new(idx.0 + offset)
     }
 
+    // Clear the slots of the frame in the range `from..to`.
+    pub(crate) unsafe fn clear(&mut self, idx: FrameIdx, from: usize, to: usize) {
+        for slot in &mut self.stack[idx.0 + from..idx.0 + to] {
+            *slot = null_mut();
+        }
+
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::clear_slots(self.stack, idx.0, from);
+    }
+
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub(crate) unsafe fn protect<'output>(
         &mut self,
         idx: FrameIdx,
@@ -202,6 +271,10 @@ where
         value: *mut c_void,
     ) -> Value<'output, 'static> {
         self.stack[idx.0 + offset] = value;
+
+        #[cfg(feature = "debug-stack")]
+        crate::debug_stack::set_slot(self.stack, idx.0, offset);
+
         Value::wrap(value.cast())
     }
 }
@@ -278,10 +351,14 @@ pub(crate) mod multitask {
 
         pub(crate) unsafe fn init(&mut self) -> JlrsResult<()> {
             if self.raw.len() < 3 {
-                Err(JlrsError::AllocError(AllocError::StackOverflow(
-                    3,
-                    self.raw.len(),
-                )))?;
+                let err: JlrsError = AllocError::StackOverflow(3, self.raw.len()).into();
+
+                // No frame exists on this stack yet, so the report of the most recently used
+                // stack must be replaced.
+                #[cfg(feature = "debug-stack")]
+                crate::debug_stack::record_empty_stack_error(&self.raw);
+
+                Err(err)?;
             }
 
             let rtls = &mut *jl_get_ptls_states();
@@ -6,6 +6,8 @@ use crate::global::Global;
 #[cfg(all(feature = "async", target_os = "linux"))]
 use crate::mode::Async;
 use crate::mode::{Mode, Sync};
+use crate::traits::private::Internal;
+use std::convert::TryInto;
 
 /// Functionality shared by [`StaticFrame`] and [`DynamicFrame`]. These structs let you protect
 /// data from garbage collection. The lifetime of a frame is assigned to the values and outputs
@@ -31,6 +33,7 @@ pub trait Frame<'frame>: private::Frame<'frame> {
     /// stack is `capacity + 2`.
     ///
     /// Returns an error if there is not enough space on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn frame<'nested, T, F: FnOnce(&mut StaticFrame<'nested, Self::U>) -> JlrsResult<T>>(
         &'nested mut self,
         capacity: usize,
@@ -42,6 +45,7 @@ pub trait Frame<'frame>: private::Frame<'frame> {
     /// of required slots on the stack is `2`.
     ///
     /// Returns an error if there is not enough space on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn dynamic_frame<'nested, T, F: FnOnce(&mut DynamicFrame<'nested, Self::U>) -> JlrsResult<T>>(
         &'nested mut self,
         func: F,
@@ -53,8 +57,46 @@ pub trait Frame<'frame>: private::Frame<'frame> {
     /// goes out of scope.
     ///
     /// Returns an error if there is not enough space on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn output(&mut self) -> JlrsResult<Output<'frame>>;
 
+    /// Returns `N` new `Output`s, this takes `N` slots on the GC stack. This is useful if you
+    /// want to return several values from a nested frame:
+    ///
+    /// ```no_run
+    /// # use jlrs::prelude::*;
+    /// # fn main() {
+    /// # let mut julia = unsafe { Julia::init(16).unwrap() };
+    /// julia.frame(2, |_global, frame| {
+    ///     let [out1, out2] = frame.outputs()?;
+    ///     let (a, b) = frame.frame(2, |frame| {
+    ///         let a = Value::eval_string(frame, "[1, 2, 3]")?.unwrap();
+    ///         let b = Value::eval_string(frame, "[4, 5, 6]")?.unwrap();
+    ///         Ok((a.extend(frame, out1), b.extend(frame, out2)))
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// Returns an error if there is not enough space on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
+    fn outputs<const N: usize>(&mut self) -> JlrsResult<[Output<'frame>; N]> {
+        // No slots must be taken if not all outputs can be created.
+        self.check_outputs(N, Internal)?;
+
+        let mut outputs = Vec::with_capacity(N);
+        for _ in 0..N {
+            outputs.push(self.output()?);
+        }
+
+        match outputs.try_into() {
+            Ok(outputs) => Ok(outputs),
+            Err(_) => unreachable!(),
+        }
+    }
+
     /// Returns the number of values belonging to this frame.
     fn size(&self) -> usize;
 
@@ -70,6 +112,7 @@ pub trait Frame<'frame>: private::Frame<'frame> {
 }
 
 impl<'frame, M: Mode> Frame<'frame> for StaticFrame<'frame, M> {
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn frame<'nested, T, F: FnOnce(&mut StaticFrame<'nested, M>) -> JlrsResult<T>>(
         &'nested mut self,
         capacity: usize,
@@ -79,6 +122,7 @@ impl<'frame, M: Mode> Frame<'frame> for StaticFrame<'frame, M> {
         func(&mut frame)
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn dynamic_frame<'nested, T, F: FnOnce(&mut DynamicFrame<'nested, M>) -> JlrsResult<T>>(
         &'nested mut self,
         func: F,
@@ -96,6 +140,7 @@ impl<'frame, M: Mode> Frame<'frame> for StaticFrame<'frame, M> {
         }
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn output(&mut self) -> JlrsResult<Output<'frame>> {
         if self.capacity == self.len {
             return Err(AllocError::FrameOverflow(1, self.len).into());
@@ -120,6 +165,7 @@ impl<'frame, M: Mode> Frame<'frame> for StaticFrame<'frame, M> {
 }
 
 impl<'frame, M: Mode> Frame<'frame> for DynamicFrame<'frame, M> {
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn dynamic_frame<'nested, T, F: FnOnce(&mut DynamicFrame<'nested, M>) -> JlrsResult<T>>(
         &'nested mut self,
         func: F,
@@ -128,6 +174,7 @@ impl<'frame, M: Mode> Frame<'frame> for DynamicFrame<'frame, M> {
         func(&mut frame)
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn frame<'nested, T, F: FnOnce(&mut StaticFrame<'nested, M>) -> JlrsResult<T>>(
         &'nested mut self,
         capacity: usize,
@@ -147,6 +194,7 @@ impl<'frame, M: Mode> Frame<'frame> for DynamicFrame<'frame, M> {
         }
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn output(&mut self) -> JlrsResult<Output<'frame>> {
         unsafe {
             let out = self.memory.new_output(self.idx)?;
@@ -165,6 +213,7 @@ impl<'frame, M: Mode> Frame<'frame> for DynamicFrame<'frame, M> {
 }
 
 impl<'frame> Frame<'frame> for NullFrame<'frame> {
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn frame<'nested, T, F: FnOnce(&mut StaticFrame<'nested, Sync>) -> JlrsResult<T>>(
         &'nested mut self,
         _: usize,
@@ -173,6 +222,7 @@ impl<'frame> Frame<'frame> for NullFrame<'frame> {
         Err(JlrsError::NullFrame)?
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn dynamic_frame<'nested, T, F: FnOnce(&mut DynamicFrame<'nested, Sync>) -> JlrsResult<T>>(
         &'nested mut self,
         _: F,
@@ -180,6 +230,7 @@ impl<'frame> Frame<'frame> for NullFrame<'frame> {
         Err(JlrsError::NullFrame)?
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn output(&mut self) -> JlrsResult<Output<'frame>> {
         Err(JlrsError::NullFrame)?
     }
@@ -193,6 +244,7 @@ impl<'frame> Frame<'frame> for NullFrame<'frame> {
 
 #[cfg(all(feature = "async", target_os = "linux"))]
 impl<'frame> Frame<'frame> for AsyncFrame<'frame> {
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn frame<'nested, T, F: FnOnce(&mut StaticFrame<'nested, Async>) -> JlrsResult<T>>(
         &'nested mut self,
         capacity: usize,
@@ -212,6 +264,7 @@ impl<'frame> Frame<'frame> for AsyncFrame<'frame> {
         }
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn dynamic_frame<'nested, T, F: FnOnce(&mut DynamicFrame<'nested, Async>) -> JlrsResult<T>>(
         &'nested mut self,
         func: F,
@@ -220,6 +273,7 @@ impl<'frame> Frame<'frame> for AsyncFrame<'frame> {
         func(&mut frame)
     }
 
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     fn output(&mut self) -> JlrsResult<Output<'frame>> {
         unsafe {
             let out = self.memory.new_output(self.idx)?;
@@ -239,7 +293,9 @@ impl<'frame> Frame<'frame> for AsyncFrame<'frame> {
 
 pub(crate) mod private {
     use super::super::{private::Internal, IntoJulia};
-    use crate::error::AllocError;
+    #[cfg(feature = "debug-stack")]
+    use crate::debug_stack::StackReport;
+    use crate::error::{AllocError, JlrsError, JlrsResult};
     #[cfg(all(feature = "async", target_os = "linux"))]
     use crate::frame::AsyncFrame;
     use crate::frame::{DynamicFrame, FrameIdx, NullFrame, Output, StaticFrame};
@@ -253,6 +309,7 @@ pub(crate) mod private {
         type U: Mode;
         // protect the value from being garbage collected while this frame is active.
         // safety: the value must be a valid Julia value
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         unsafe fn protect(
             &mut self,
             value: *mut jl_value_t,
@@ -260,6 +317,7 @@ pub(crate) mod private {
         ) -> Result<Value<'frame, 'static>, AllocError>;
 
         // Create and protect multiple values from being garbage collected while this frame is active.
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many<P: IntoJulia>(
             &mut self,
             values: &[P],
@@ -267,6 +325,7 @@ pub(crate) mod private {
         ) -> Result<Values<'frame>, AllocError>;
 
         // Create and protect multiple values from being garbage collected while this frame is active.
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many_dyn(
             &mut self,
             values: &[&dyn IntoJulia],
@@ -280,10 +339,18 @@ pub(crate) mod private {
             value: *mut jl_value_t,
             _: Internal,
         ) -> Value<'output, 'static>;
+
+        // Returns an error if `n` outputs can't be created.
+        fn check_outputs(&self, n: usize, _: Internal) -> JlrsResult<()>;
+
+        // Returns a report of the GC stack this frame has been created on.
+        #[cfg(feature = "debug-stack")]
+        fn stack_report(&self, _: Internal) -> Option<StackReport>;
     }
 
     impl<'frame, M: Mode> Frame<'frame> for StaticFrame<'frame, M> {
         type U = M;
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         unsafe fn protect(
             &mut self,
             value: *mut jl_value_t,
@@ -302,6 +369,7 @@ pub(crate) mod private {
             Ok(out)
         }
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many<P: IntoJulia>(
             &mut self,
             values: &[P],
@@ -323,6 +391,7 @@ pub(crate) mod private {
             }
         }
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many_dyn(
             &mut self,
             values: &[&dyn IntoJulia],
@@ -355,10 +424,24 @@ pub(crate) mod private {
                     .protect(FrameIdx::default(), output.offset, value.cast())
             }
         }
+
+        fn check_outputs(&self, n: usize, _: Internal) -> JlrsResult<()> {
+            if self.capacity < self.len + n {
+                return Err(AllocError::FrameOverflow(n, self.capacity).into());
+            }
+
+            Ok(())
+        }
+
+        #[cfg(feature = "debug-stack")]
+        fn stack_report(&self, _: Internal) -> Option<StackReport> {
+            self.memory.report()
+        }
     }
 
     impl<'frame, M: Mode> Frame<'frame> for DynamicFrame<'frame, M> {
         type U = M;
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         unsafe fn protect(
             &mut self,
             value: *mut jl_value_t,
@@ -369,6 +452,7 @@ pub(crate) mod private {
             Ok(out)
         }
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many<P: IntoJulia>(
             &mut self,
             values: &[P],
@@ -393,6 +477,7 @@ pub(crate) mod private {
             }
         }
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many_dyn(
             &mut self,
             values: &[&dyn IntoJulia],
@@ -419,10 +504,20 @@ pub(crate) mod private {
         ) -> Value<'output, 'static> {
             unsafe { self.memory.protect_output(output, value.cast()) }
         }
+
+        fn check_outputs(&self, n: usize, _: Internal) -> JlrsResult<()> {
+            self.memory.check_outputs(n)
+        }
+
+        #[cfg(feature = "debug-stack")]
+        fn stack_report(&self, _: Internal) -> Option<StackReport> {
+            self.memory.report()
+        }
     }
 
     impl<'frame> Frame<'frame> for NullFrame<'frame> {
         type U = Sync;
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         unsafe fn protect(
             &mut self,
             _: *mut jl_value_t,
@@ -431,6 +526,7 @@ pub(crate) mod private {
             Err(AllocError::FrameOverflow(1, 0))
         }
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many<P: IntoJulia>(
             &mut self,
             values: &[P],
@@ -439,6 +535,7 @@ pub(crate) mod private {
             Err(AllocError::FrameOverflow(values.len(), 0))
         }
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many_dyn(
             &mut self,
             values: &[&dyn IntoJulia],
@@ -455,12 +552,22 @@ pub(crate) mod private {
         ) -> Value<'output, 'static> {
             unreachable!()
         }
+
+        fn check_outputs(&self, _: usize, _: Internal) -> JlrsResult<()> {
+            Err(JlrsError::NullFrame)?
+        }
+
+        #[cfg(feature = "debug-stack")]
+        fn stack_report(&self, _: Internal) -> Option<StackReport> {
+            None
+        }
     }
 
     #[cfg(all(feature = "async", target_os = "linux"))]
     impl<'frame> Frame<'frame> for AsyncFrame<'frame> {
         type U = Async;
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         unsafe fn protect(
             &mut self,
             value: *mut jl_value_t,
@@ -471,6 +578,7 @@ pub(crate) mod private {
             Ok(out)
         }
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many<P: IntoJulia>(
             &mut self,
             values: &[P],
@@ -495,6 +603,7 @@ pub(crate) mod private {
             }
         }
 
+        #[cfg_attr(feature = "debug-stack", track_caller)]
         fn create_many_dyn(
             &mut self,
             values: &[&dyn IntoJulia],
@@ -521,5 +630,14 @@ pub(crate) mod private {
         ) -> Value<'output, 'static> {
             unsafe { self.memory.protect_output(output, value.cast()) }
         }
+
+        fn check_outputs(&self, n: usize, _: Internal) -> JlrsResult<()> {
+            self.memory.check_outputs(n)
+        }
+
+        #[cfg(feature = "debug-stack")]
+        fn stack_report(&self, _: Internal) -> Option<StackReport> {
+            self.memory.report()
+        }
     }
 }
@@ -1,8 +1,16 @@
 //! Control the garbage collector.
 
+use crate::error::{JlrsError, JlrsResult};
+use crate::global::Global;
 use crate::traits::Frame;
+use crate::value::module::Module;
 use crate::Julia;
-use jl_sys::{jl_gc_collect, jl_gc_collection_t, jl_gc_enable, jl_gc_is_enabled};
+use jl_sys::{
+    jl_gc_collect, jl_gc_collection_t, jl_gc_enable, jl_gc_is_enabled, jl_gc_safepoint,
+    jl_unbox_int64,
+};
+use std::marker::PhantomData;
+use std::mem::size_of;
 
 /// The different collection modes.
 #[derive(Debug, Copy, Clone)]
@@ -28,6 +36,212 @@ pub trait Gc: private::Gc {
     unsafe fn gc_collect(&mut self, mode: GcCollection) {
         jl_gc_collect(mode as jl_gc_collection_t)
     }
+
+    /// Disable the GC until the returned [`GcDisabled`] is dropped, the GC is enabled again when
+    /// it's dropped if it was enabled when this method was called. Because the GC is enabled
+    /// again when the guard is dropped, this also happens if your code panics.
+    ///
+    /// # Safety
+    ///
+    /// Guards must be dropped in the reverse order in which they have been created, and the GC
+    /// must not be enabled with [`Gc::enable_gc`] while a guard exists. Otherwise the GC can be
+    /// enabled while code that relies on it being disabled is still running.
+    ///
+    /// [`GcDisabled`]: struct.GcDisabled.html
+    /// [`Gc::enable_gc`]: trait.Gc.html#method.enable_gc
+    unsafe fn gc_disabled(&mut self) -> GcDisabled {
+        GcDisabled {
+            was_enabled: jl_gc_enable(0) == 1,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Insert a safepoint, the GC can run while a thread is at a safepoint. If your code doesn't
+    /// allocate for a long time, you should call this method periodically to prevent other
+    /// threads that need to allocate from being blocked.
+    fn gc_safepoint(&mut self) {
+        unsafe { jl_gc_safepoint() }
+    }
+
+    /// Set the number of bytes that can be allocated before the GC runs again. Julia 1.5 doesn't
+    /// support changing the collection interval, so this method always returns
+    /// `JlrsError::Unsupported`.
+    fn gc_set_interval(&mut self, _bytes: usize) -> JlrsResult<()> {
+        Err(JlrsError::Unsupported(
+            "Setting the collection interval".into(),
+            crate::JULIA_VERSION.into(),
+        ))?
+    }
+
+    /// Set a hint for the maximum size of the heap, the GC runs more often when the heap
+    /// approaches this size. Julia 1.5 doesn't support heap size hints, so this method always
+    /// returns `JlrsError::Unsupported`.
+    fn gc_set_heap_size_hint(&mut self, _bytes: usize) -> JlrsResult<()> {
+        Err(JlrsError::Unsupported(
+            "Setting a heap size hint".into(),
+            crate::JULIA_VERSION.into(),
+        ))?
+    }
+
+    /// Returns the statistics collected by the GC, this is equivalent to calling `Base.gc_num()`.
+    ///
+    /// Returns an error if the layout of `Base.GC_Num` is not supported.
+    fn gc_stats(&mut self) -> JlrsResult<GcStats> {
+        unsafe {
+            let global = Global::new();
+            let num = Module::base(global)
+                .function("gc_num")?
+                .call0_unprotected(global)
+                .map_err(|e| JlrsError::Exception(e.type_name().into()))?;
+
+            let ty = num.datatype().ok_or(JlrsError::WrongType)?;
+            if ty.name() != "GC_Num" || ty.size() as usize != size_of::<GcNum>() {
+                Err(JlrsError::WrongType)?;
+            }
+
+            // The result isn't rooted, it must be read before anything else is allocated.
+            let num = num.ptr().cast::<GcNum>().read();
+            Ok(GcStats::from(num))
+        }
+    }
+
+    /// Returns the number of bytes that are in use by live objects, this is equivalent to calling
+    /// `Base.gc_live_bytes()`.
+    fn gc_live_bytes(&mut self) -> JlrsResult<usize> {
+        unsafe {
+            let global = Global::new();
+            let bytes = Module::base(global)
+                .function("gc_live_bytes")?
+                .call0_unprotected(global)
+                .map_err(|e| JlrsError::Exception(e.type_name().into()))?;
+
+            Ok(jl_unbox_int64(bytes.ptr()) as usize)
+        }
+    }
+}
+
+/// Statistics collected by the GC, returned by [`Gc::gc_stats`]. The counters that track the
+/// number of allocations and the amount of memory that has been allocated are reset every time
+/// the GC runs, use [`GcStats::total_allocd`] to find the total number of bytes that have been
+/// allocated.
+///
+/// [`Gc::gc_stats`]: trait.Gc.html#method.gc_stats
+/// [`GcStats::total_allocd`]: struct.GcStats.html#method.total_allocd
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GcStats {
+    /// Bytes allocated since the GC last ran.
+    pub allocd: i64,
+    /// Bytes freed since the GC last ran.
+    pub freed: i64,
+    /// The number of calls to `malloc`.
+    pub malloc: u64,
+    /// The number of calls to `realloc`.
+    pub realloc: u64,
+    /// The number of allocations in the pool allocator.
+    pub poolalloc: u64,
+    /// The number of allocations of big objects.
+    pub bigalloc: u64,
+    /// The number of calls to `free`.
+    pub freecall: u64,
+    /// The total time spent in the GC in nanoseconds.
+    pub total_time: u64,
+    /// The number of times the GC has run.
+    pub pause: usize,
+    /// The number of full collections.
+    pub full_sweep: usize,
+    /// The number of allocated bytes that triggers the next collection.
+    pub interval: usize,
+    deferred_alloc: i64,
+    total_allocd: u64,
+}
+
+impl GcStats {
+    /// Returns the total number of bytes that have been allocated, this is equivalent to calling
+    /// `Base.gc_total_bytes` with these statistics.
+    pub fn total_allocd(&self) -> i64 {
+        self.allocd
+            .wrapping_add(self.deferred_alloc)
+            .wrapping_add(self.total_allocd as i64)
+    }
+
+    /// Returns the statistics of the period between `earlier` and `self`, this is equivalent to
+    /// calling `Base.GC_Diff`. This can be used to profile a section of your code.
+    pub fn diff(&self, earlier: &GcStats) -> GcStats {
+        GcStats {
+            allocd: self.total_allocd().wrapping_sub(earlier.total_allocd()),
+            freed: self.freed.wrapping_sub(earlier.freed),
+            malloc: self.malloc.wrapping_sub(earlier.malloc),
+            realloc: self.realloc.wrapping_sub(earlier.realloc),
+            poolalloc: self.poolalloc.wrapping_sub(earlier.poolalloc),
+            bigalloc: self.bigalloc.wrapping_sub(earlier.bigalloc),
+            freecall: self.freecall.wrapping_sub(earlier.freecall),
+            total_time: self.total_time.wrapping_sub(earlier.total_time),
+            pause: self.pause.wrapping_sub(earlier.pause),
+            full_sweep: self.full_sweep.wrapping_sub(earlier.full_sweep),
+            interval: self.interval,
+            deferred_alloc: 0,
+            total_allocd: 0,
+        }
+    }
+}
+
+impl From<GcNum> for GcStats {
+    fn from(num: GcNum) -> Self {
+        GcStats {
+            allocd: num.allocd,
+            freed: num.freed,
+            malloc: num.malloc,
+            realloc: num.realloc,
+            poolalloc: num.poolalloc,
+            bigalloc: num.bigalloc,
+            freecall: num.freecall,
+            total_time: num.total_time,
+            pause: num.pause as usize,
+            full_sweep: num.full_sweep as usize,
+            interval: num.collect,
+            deferred_alloc: num.deferred_alloc,
+            total_allocd: num.total_allocd,
+        }
+    }
+}
+
+// The layout of Base.GC_Num
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct GcNum {
+    allocd: i64,
+    deferred_alloc: i64,
+    freed: i64,
+    malloc: u64,
+    realloc: u64,
+    poolalloc: u64,
+    bigalloc: u64,
+    freecall: u64,
+    total_time: u64,
+    total_allocd: u64,
+    since_sweep: u64,
+    collect: usize,
+    pause: i32,
+    full_sweep: i32,
+}
+
+/// A guard that disables the GC until it's dropped, returned by [`Gc::gc_disabled`].
+///
+/// [`Gc::gc_disabled`]: trait.Gc.html#method.gc_disabled
+pub struct GcDisabled {
+    was_enabled: bool,
+    // The GC must be enabled again on the same thread.
+    _marker: PhantomData<*mut ()>,
+}
+
+impl Drop for GcDisabled {
+    fn drop(&mut self) {
+        if self.was_enabled {
+            unsafe {
+                jl_gc_enable(1);
+            }
+        }
+    }
 }
 
 impl Gc for Julia {}
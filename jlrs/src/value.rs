@@ -89,10 +89,12 @@ use self::module::Module;
 use self::symbol::Symbol;
 use self::type_var::TypeVar;
 use self::union_all::UnionAll;
+use self::value_ref::ValueRef;
 use crate::error::{JlrsError, JlrsResult};
 use crate::frame::Output;
 use crate::global::Global;
 use crate::impl_julia_type;
+use crate::persistent::GcPreserve;
 use crate::traits::{
     private::Internal, valid_layout::ValidLayout, Cast, Frame, IntoJulia, JuliaType,
     JuliaTypecheck, TemporarySymbol,
@@ -100,23 +102,25 @@ use crate::traits::{
 use jl_sys::{
     jl_alloc_array_1d, jl_alloc_array_2d, jl_alloc_array_3d, jl_an_empty_string,
     jl_an_empty_vec_any, jl_any_type, jl_apply_array_type, jl_apply_tuple_type_v, jl_apply_type,
-    jl_array_any_type, jl_array_int32_type, jl_array_symbol_type, jl_array_uint8_type,
-    jl_bottom_type, jl_call, jl_call0, jl_call1, jl_call2, jl_call3, jl_datatype_t,
-    jl_diverror_exception, jl_egal, jl_emptytuple, jl_eval_string, jl_exception_occurred, jl_false,
-    jl_field_index, jl_field_isptr, jl_field_names, jl_fieldref, jl_fieldref_noalloc, jl_finalize,
-    jl_gc_add_finalizer, jl_gc_wb, jl_get_kwsorter, jl_get_nth_field, jl_get_nth_field_noalloc,
-    jl_interrupt_exception, jl_is_kind, jl_isa, jl_memory_exception, jl_new_array,
-    jl_new_struct_uninit, jl_new_structv, jl_nfields, jl_nothing, jl_nothing_type, jl_object_id,
-    jl_ptr_to_array, jl_ptr_to_array_1d, jl_readonlymemory_exception, jl_set_nth_field,
-    jl_stackovf_exception, jl_subtype, jl_svec_data, jl_svec_len, jl_true, jl_type_union,
-    jl_type_unionall, jl_typeof, jl_typeof_str, jl_undefref_exception, jl_value_t,
+    jl_array_any_type, jl_array_data, jl_array_int32_type, jl_array_len, jl_array_ptr_set,
+    jl_array_symbol_type, jl_array_t, jl_array_uint8_type, jl_bottom_type, jl_call, jl_call0,
+    jl_call1, jl_call2, jl_call3, jl_datatype_size, jl_datatype_t, jl_diverror_exception, jl_egal,
+    jl_emptytuple, jl_eval_string, jl_exception_occurred, jl_false, jl_field_index, jl_field_isptr,
+    jl_field_names, jl_fieldref, jl_fieldref_noalloc, jl_finalize, jl_gc_add_finalizer, jl_gc_wb,
+    jl_get_kwsorter, jl_get_nth_field, jl_get_nth_field_noalloc, jl_interrupt_exception,
+    jl_is_kind, jl_isa, jl_memory_exception, jl_new_array, jl_new_struct_uninit, jl_new_structv,
+    jl_nfields, jl_nothing, jl_nothing_type, jl_object_id, jl_ptr_to_array, jl_ptr_to_array_1d,
+    jl_readonlymemory_exception, jl_set_nth_field, jl_stackovf_exception, jl_subtype, jl_svec_data,
+    jl_svec_len, jl_true, jl_type_union, jl_type_unionall, jl_typeof, jl_typeof_str,
+    jl_undefref_exception, jl_value_t,
 };
 use smallvec::SmallVec;
 use std::borrow::BorrowMut;
 use std::cell::UnsafeCell;
-use std::ffi::{CStr, CString};
+use std::ffi::{c_void, CStr, CString};
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::marker::PhantomData;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::ptr::null_mut;
 use std::slice;
 
@@ -145,6 +149,7 @@ pub mod typemap_entry;
 pub mod typemap_level;
 pub mod union;
 pub mod union_all;
+pub mod value_ref;
 pub mod weak_ref;
 
 thread_local! {
@@ -333,6 +338,7 @@ impl<'frame, 'data> Value<'frame, 'data> {
     /// returns an error if no slot is available.
     ///
     /// [`IntoJulia`]: ../traits/trait.IntoJulia.html
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn new<V, F>(frame: &mut F, value: V) -> JlrsResult<Value<'frame, 'static>>
     where
         V: IntoJulia,
@@ -409,11 +415,12 @@ impl<'frame, 'data> Value<'frame, 'data> {
 
     /// Allocates a new n-dimensional array in Julia.
     ///
-    /// Creating an an array with 1, 2 or 3 dimensions requires one slot on the GC stack. If you
+    /// Creating an array with 1, 2 or 3 dimensions requires one slot on the GC stack. If you
     /// create an array with more dimensions an extra frame is created with a single slot,
     /// temporarily taking 3 additional slots.
     ///
     /// This function returns an error if there are not enough slots available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn new_array<T, D, F>(frame: &mut F, dimensions: D) -> JlrsResult<Value<'frame, 'static>>
     where
         T: IntoJulia + JuliaType,
@@ -456,6 +463,7 @@ impl<'frame, 'data> Value<'frame, 'data> {
     /// taking 3 additional slots.
     ///
     /// This function returns an error if there are not enough slots available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn borrow_array<T, D, V, F>(
         frame: &mut F,
         data: &'data mut V,
@@ -506,6 +514,7 @@ impl<'frame, 'data> Value<'frame, 'data> {
     /// taking 3 additional slots.
     ///
     /// This function returns an error if there are not enough slots available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn move_array<T, D, F>(
         frame: &mut F,
         data: Vec<T>,
@@ -568,6 +577,225 @@ impl<'frame, 'data> Value<'frame, 'data> {
         }
     }
 
+    /// Allocates a new n-dimensional array in Julia with elements of type `elty`, which can be
+    /// any Julia type including non-bits types, abstract types and unions. The contents of the
+    /// array are undefined; if the elements are not stored inline they're `#undef`.
+    ///
+    /// Creating an array with 1, 2 or 3 dimensions requires one slot on the GC stack. If you
+    /// create an array with more dimensions an extra frame is created with a single slot,
+    /// temporarily taking 3 additional slots.
+    ///
+    /// This function returns an error if `elty` is not a type or there are not enough slots
+    /// available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
+    pub fn new_array_of_type<D, F>(
+        frame: &mut F,
+        elty: Value,
+        dimensions: D,
+    ) -> JlrsResult<Value<'frame, 'static>>
+    where
+        D: Into<Dimensions>,
+        F: Frame<'frame>,
+    {
+        if !elty.is_type() {
+            Err(JlrsError::NotAType(elty.type_name().into()))?;
+        }
+
+        unsafe {
+            let array = new_array_of_type(frame, elty.ptr(), dimensions)?;
+            frame.protect(array, Internal).map_err(Into::into)
+        }
+    }
+
+    /// Allocates a new n-dimensional array in Julia with elements of type `elty` using an
+    /// `Output`. The contents of the array are undefined; if the elements are not stored inline
+    /// they're `#undef`.
+    ///
+    /// Because an `Output` is used, no additional slot in the current frame is used if you create
+    /// an array with 1, 2 or 3 dimensions. If you create an array with more dimensions an extra
+    /// frame is created with a single slot, temporarily taking 3 additional slots.
+    ///
+    /// This function returns an error if `elty` is not a type or there are not enough slots
+    /// available.
+    pub fn new_array_of_type_output<'output, D, F>(
+        frame: &mut F,
+        output: Output<'output>,
+        elty: Value,
+        dimensions: D,
+    ) -> JlrsResult<Value<'output, 'static>>
+    where
+        D: Into<Dimensions>,
+        F: Frame<'frame>,
+    {
+        if !elty.is_type() {
+            Err(JlrsError::NotAType(elty.type_name().into()))?;
+        }
+
+        unsafe {
+            let array = new_array_of_type(frame, elty.ptr(), dimensions)?;
+            Ok(frame.assign_output(output, array, Internal))
+        }
+    }
+
+    /// Allocates a new n-dimensional array in Julia and sets every element to `value`, similar
+    /// to `fill(value, dims)` in Julia. The element type of the array is the type of `value`.
+    ///
+    /// Creating an array with 1, 2 or 3 dimensions requires one slot on the GC stack. If you
+    /// create an array with more dimensions an extra frame is created with a single slot,
+    /// temporarily taking 3 additional slots.
+    ///
+    /// This function returns an error if there are not enough slots available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
+    pub fn filled_array<'value, 'borrow, D, F>(
+        frame: &mut F,
+        value: Value<'value, 'borrow>,
+        dimensions: D,
+    ) -> JlrsResult<Value<'frame, 'borrow>>
+    where
+        D: Into<Dimensions>,
+        F: Frame<'frame>,
+    {
+        unsafe {
+            let array = new_array_of_type(frame, jl_typeof(value.ptr()), dimensions)?;
+            fill_array(array, value);
+            frame.protect(array, Internal).map_err(Into::into)
+        }
+    }
+
+    /// Allocates a new n-dimensional array in Julia and sets every element to `value` using an
+    /// `Output`. The element type of the array is the type of `value`.
+    ///
+    /// Because an `Output` is used, no additional slot in the current frame is used if you create
+    /// an array with 1, 2 or 3 dimensions. If you create an array with more dimensions an extra
+    /// frame is created with a single slot, temporarily taking 3 additional slots.
+    ///
+    /// This function returns an error if there are not enough slots available.
+    pub fn filled_array_output<'output, 'value, 'borrow, D, F>(
+        frame: &mut F,
+        output: Output<'output>,
+        value: Value<'value, 'borrow>,
+        dimensions: D,
+    ) -> JlrsResult<Value<'output, 'borrow>>
+    where
+        D: Into<Dimensions>,
+        F: Frame<'frame>,
+    {
+        unsafe {
+            let array = new_array_of_type(frame, jl_typeof(value.ptr()), dimensions)?;
+            fill_array(array, value);
+            Ok(frame.assign_output(output, array, Internal))
+        }
+    }
+
+    /// Allocates a new n-dimensional array in Julia whose elements are zero-initialized, similar
+    /// to `zeros(T, dims)` in Julia.
+    ///
+    /// Creating an array with 1, 2 or 3 dimensions requires one slot on the GC stack. If you
+    /// create an array with more dimensions an extra frame is created with a single slot,
+    /// temporarily taking 3 additional slots.
+    ///
+    /// This function returns an error if there are not enough slots available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
+    pub fn zeros_array<T, D, F>(frame: &mut F, dimensions: D) -> JlrsResult<Value<'frame, 'static>>
+    where
+        T: IntoJulia + JuliaType,
+        D: Into<Dimensions>,
+        F: Frame<'frame>,
+    {
+        unsafe {
+            let array = new_array::<T, _, _>(frame, dimensions)?;
+            zero_array(array);
+            frame.protect(array, Internal).map_err(Into::into)
+        }
+    }
+
+    /// Allocates a new n-dimensional array in Julia whose elements are zero-initialized using an
+    /// `Output`.
+    ///
+    /// Because an `Output` is used, no additional slot in the current frame is used if you create
+    /// an array with 1, 2 or 3 dimensions. If you create an array with more dimensions an extra
+    /// frame is created with a single slot, temporarily taking 3 additional slots.
+    ///
+    /// This function returns an error if there are not enough slots available.
+    pub fn zeros_array_output<'output, T, D, F>(
+        frame: &mut F,
+        output: Output<'output>,
+        dimensions: D,
+    ) -> JlrsResult<Value<'output, 'static>>
+    where
+        T: IntoJulia + JuliaType,
+        D: Into<Dimensions>,
+        F: Frame<'frame>,
+    {
+        unsafe {
+            let array = new_array::<T, _, _>(frame, dimensions)?;
+            zero_array(array);
+            Ok(frame.assign_output(output, array, Internal))
+        }
+    }
+
+    /// Allocates a new n-dimensional array in Julia with elements of type `elty` whose data is
+    /// zero-initialized. If the elements are stored inline every bit of their data is set to
+    /// zero, otherwise they're `#undef`.
+    ///
+    /// Creating an array with 1, 2 or 3 dimensions requires one slot on the GC stack. If you
+    /// create an array with more dimensions an extra frame is created with a single slot,
+    /// temporarily taking 3 additional slots.
+    ///
+    /// This function returns an error if `elty` is not a type or there are not enough slots
+    /// available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
+    pub fn zeros_array_of_type<D, F>(
+        frame: &mut F,
+        elty: Value,
+        dimensions: D,
+    ) -> JlrsResult<Value<'frame, 'static>>
+    where
+        D: Into<Dimensions>,
+        F: Frame<'frame>,
+    {
+        if !elty.is_type() {
+            Err(JlrsError::NotAType(elty.type_name().into()))?;
+        }
+
+        unsafe {
+            let array = new_array_of_type(frame, elty.ptr(), dimensions)?;
+            zero_array(array);
+            frame.protect(array, Internal).map_err(Into::into)
+        }
+    }
+
+    /// Allocates a new n-dimensional array in Julia with elements of type `elty` whose data is
+    /// zero-initialized using an `Output`. If the elements are stored inline every bit of their
+    /// data is set to zero, otherwise they're `#undef`.
+    ///
+    /// Because an `Output` is used, no additional slot in the current frame is used if you create
+    /// an array with 1, 2 or 3 dimensions. If you create an array with more dimensions an extra
+    /// frame is created with a single slot, temporarily taking 3 additional slots.
+    ///
+    /// This function returns an error if `elty` is not a type or there are not enough slots
+    /// available.
+    pub fn zeros_array_of_type_output<'output, D, F>(
+        frame: &mut F,
+        output: Output<'output>,
+        elty: Value,
+        dimensions: D,
+    ) -> JlrsResult<Value<'output, 'static>>
+    where
+        D: Into<Dimensions>,
+        F: Frame<'frame>,
+    {
+        if !elty.is_type() {
+            Err(JlrsError::NotAType(elty.type_name().into()))?;
+        }
+
+        unsafe {
+            let array = new_array_of_type(frame, elty.ptr(), dimensions)?;
+            zero_array(array);
+            Ok(frame.assign_output(output, array, Internal))
+        }
+    }
+
     /// Returns the union of all types in `types`. For each of these types, [`Value::is_kind`]
     /// must return `true`. TNote that the result is not necessarily a [`Union`], for example the
     /// union of a single [`DataType`] is that type, not a `Union` with a single variant. One free
@@ -576,15 +804,13 @@ impl<'frame, 'data> Value<'frame, 'data> {
     /// [`Value::is_kind`]: struct.Value.html#method.is_kind
     /// [`Union`]: union/struct.Union.html
     /// [`DataType`]: datatype/struct.DataType.html
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn new_union<F>(frame: &mut F, types: &mut [Value]) -> JlrsResult<Self>
     where
         F: Frame<'frame>,
     {
         unsafe {
-            if let Some(v) = types
-                .iter()
-                .find_map(|v| if v.is_kind() { None } else { Some(v) })
-            {
+            if let Some(v) = types.iter().find(|v| !v.is_kind()) {
                 Err(JlrsError::NotAKind(v.type_name().into()))?;
             }
 
@@ -593,8 +819,31 @@ impl<'frame, 'data> Value<'frame, 'data> {
         }
     }
 
+    /// Create a new `Union` and use the output to protect it from garbage collection. See
+    /// [`Value::new_union`] for more information.
+    ///
+    /// [`Value::new_union`]: struct.Value.html#method.new_union
+    pub fn new_union_output<'output, F>(
+        frame: &mut F,
+        output: Output<'output>,
+        types: &mut [Value],
+    ) -> JlrsResult<Value<'output, 'static>>
+    where
+        F: Frame<'frame>,
+    {
+        unsafe {
+            if let Some(v) = types.iter().find(|v| !v.is_kind()) {
+                Err(JlrsError::NotAKind(v.type_name().into()))?;
+            }
+
+            let un = jl_type_union(types.as_mut_ptr().cast(), types.len());
+            Ok(frame.assign_output(output, un, Internal))
+        }
+    }
+
     /// Create a new `UnionAll`. One free slot on the GC stack is required for this function to
     /// succeed, returns an error if no slot is available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn new_unionall<F>(frame: &mut F, tvar: TypeVar, body: Value) -> JlrsResult<Self>
     where
         F: Frame<'frame>,
@@ -609,6 +858,26 @@ impl<'frame, 'data> Value<'frame, 'data> {
         }
     }
 
+    /// Create a new `UnionAll` and use the output to protect it from garbage collection.
+    pub fn new_unionall_output<'output, F>(
+        frame: &mut F,
+        output: Output<'output>,
+        tvar: TypeVar,
+        body: Value,
+    ) -> JlrsResult<Value<'output, 'static>>
+    where
+        F: Frame<'frame>,
+    {
+        if !body.is_type() && !body.is::<TypeVar>() {
+            Err(JlrsError::InvalidBody(body.type_name().into()))?;
+        }
+
+        unsafe {
+            let ua = jl_type_unionall(tvar.ptr(), body.ptr());
+            Ok(frame.assign_output(output, ua, Internal))
+        }
+    }
+
     /// Create a new named tuple, you can use the `named_tuple` macro instead of this method.
     pub fn new_named_tuple<'value, 'borrow, F, S, T, V>(
         frame: &mut F,
@@ -682,6 +951,7 @@ impl<'frame, 'data> Value<'frame, 'data> {
     ///
     /// One free slot on the GC stack is required for this function to succeed, returns an error
     /// if no slot is available.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn apply_type<'fr, 'value, 'borrow, F, V>(
         self,
         frame: &mut F,
@@ -697,6 +967,29 @@ impl<'frame, 'data> Value<'frame, 'data> {
             frame.protect(applied, Internal).map_err(Into::into)
         }
     }
+
+    /// Apply the given types to `self` and use the output to protect the result from garbage
+    /// collection. See [`Value::apply_type`] for more information.
+    ///
+    /// If the types cannot be applied to `self` your program will abort.
+    ///
+    /// [`Value::apply_type`]: struct.Value.html#method.apply_type
+    pub fn apply_type_output<'output, 'fr, 'value, 'borrow, F, V>(
+        self,
+        frame: &mut F,
+        output: Output<'output>,
+        types: &mut V,
+    ) -> Value<'output, 'borrow>
+    where
+        F: Frame<'fr>,
+        V: AsMut<[Value<'value, 'borrow>]>,
+    {
+        unsafe {
+            let types = types.as_mut();
+            let applied = jl_apply_type(self.ptr(), types.as_mut_ptr().cast(), types.len());
+            frame.assign_output(output, applied, Internal)
+        }
+    }
 }
 
 /// # Properties
@@ -845,6 +1138,18 @@ impl<'frame, 'data> Value<'frame, 'data> {
     {
         unsafe { frame.assign_output(output, self.ptr().cast(), Internal) }
     }
+
+    /// Preserve this value until the returned [`GcPreserve`] is dropped. Unlike a value that is
+    /// rooted in a frame, the preserved value can be used without borrowing the frame. This
+    /// temporarily takes two slots on the GC stack.
+    ///
+    /// [`GcPreserve`]: ../persistent/struct.GcPreserve.html
+    pub fn preserve<'fr, F>(self, frame: &mut F) -> JlrsResult<GcPreserve<'data>>
+    where
+        F: Frame<'fr>,
+    {
+        GcPreserve::new(frame, &[self])
+    }
 }
 
 /// # Casting to Rust
@@ -910,6 +1215,7 @@ impl<'frame, 'data> Value<'frame, 'data> {
     /// `JlrsError::OutOfBounds` is returned. This function assumes the field must be protected
     /// from garbage collection, so calling this function will take a single slot on the GC stack.
     /// If there is no slot available `JlrsError::AllocError` is returned.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn get_nth_field<'fr, F>(self, frame: &mut F, idx: usize) -> JlrsResult<Value<'fr, 'data>>
     where
         F: Frame<'fr>,
@@ -970,10 +1276,27 @@ impl<'frame, 'data> Value<'frame, 'data> {
         Ok(Value::wrap(jl_fieldref_noalloc(self.ptr(), idx)))
     }
 
+    /// Returns a reference to the field at index `idx` if it exists and is a pointer to another
+    /// value. This takes no space on the GC stack, the reference can be used to access the
+    /// fields of that value in turn, and can be rooted when it's needed as a `Value`. See
+    /// [`ValueRef`] for more information.
+    ///
+    /// If the field does not exist `JlrsError::OutOfBounds` is returned. If the field is stored
+    /// inline, `JlrsError::NotAPointerField` is returned.
+    ///
+    /// [`ValueRef`]: value_ref/struct.ValueRef.html
+    pub fn get_nth_field_ref(self, idx: usize) -> JlrsResult<ValueRef<'frame, 'data>> {
+        unsafe {
+            let field = self.get_nth_field_noalloc(idx)?;
+            Ok(ValueRef::wrap(field.ptr()))
+        }
+    }
+
     /// Returns the field with the name `field_name` if it exists. If it does not exist
     /// `JlrsError::NoSuchField` is returned. This function assumes the field must be protected
     /// from garbage collection, so calling this function will take a single slot on the GC stack.
     /// If there is no slot available `JlrsError::AllocError` is returned.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn get_field<'fr, N, F>(self, frame: &mut F, field_name: N) -> JlrsResult<Value<'fr, 'data>>
     where
         N: TemporarySymbol,
@@ -1062,6 +1385,24 @@ impl<'frame, 'data> Value<'frame, 'data> {
         Ok(Value::wrap(jl_get_nth_field_noalloc(self.ptr(), idx as _)))
     }
 
+    /// Returns a reference to the field with the name `field_name` if it exists and is a pointer
+    /// to another value. This takes no space on the GC stack, see [`Value::get_nth_field_ref`]
+    /// for more information.
+    ///
+    /// If the field does not exist `JlrsError::NoSuchField` is returned. If the field is stored
+    /// inline, `JlrsError::NotAPointerField` is returned.
+    ///
+    /// [`Value::get_nth_field_ref`]: struct.Value.html#method.get_nth_field_ref
+    pub fn get_field_ref<N>(self, field_name: N) -> JlrsResult<ValueRef<'frame, 'data>>
+    where
+        N: TemporarySymbol,
+    {
+        unsafe {
+            let field = self.get_field_noalloc(field_name)?;
+            Ok(ValueRef::wrap(field.ptr()))
+        }
+    }
+
     /// Set the value of the field at `idx`. Returns an error if this value is immutable or if the
     /// type of `value` is not a subtype of the field type. This is unsafe because the previous
     /// value of this field can become unrooted if you're directly using it from Rust.
@@ -1150,6 +1491,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// Execute a Julia command `cmd`, for example
     ///
     /// `Value::eval_string(frame, "sqrt(2)")`.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn eval_string<'frame, F, S>(
         frame: &mut F,
         cmd: S,
@@ -1169,6 +1511,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
 
     /// Execute a Julia command `cmd`. This is equivalent to `Value::eval_string`, but uses a
     /// null-terminated string.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn eval_cstring<'frame, F, S>(
         frame: &mut F,
         cmd: S,
@@ -1188,6 +1531,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// Call this value as a function that takes zero arguments, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call0<'frame, F>(self, frame: &mut F) -> JlrsResult<CallResult<'frame, 'static>>
     where
         F: Frame<'frame>,
@@ -1215,6 +1559,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// Call this value as a function that takes one argument, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call1<'frame, 'borrow, F>(
         self,
         frame: &mut F,
@@ -1250,6 +1595,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// Call this value as a function that takes two arguments, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call2<'frame, 'borrow, F>(
         self,
         frame: &mut F,
@@ -1287,6 +1633,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// Call this value as a function that takes three arguments, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call3<'frame, 'borrow, F>(
         self,
         frame: &mut F,
@@ -1326,6 +1673,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// Call this value as a function that takes several arguments, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call<'frame, 'value, 'borrow, V, F>(
         self,
         frame: &mut F,
@@ -1414,6 +1762,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// Call this value as a function that takes several arguments in a single `Values`, this
     /// takes one slot on the GC stack. Returns the result of this function call if no exception
     /// is thrown, the exception if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call_values<'frame, F>(
         self,
         frame: &mut F,
@@ -1450,6 +1799,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// anonymous function with some arguments will call the value as a function with those
     /// arguments and return its result, or catch the exception, print the stackstrace, and
     /// rethrow that exception. This takes one slot on the GC stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn tracing_call<'frame, F>(self, frame: &mut F) -> JlrsResult<CallResult<'frame, 'data>>
     where
         F: Frame<'frame>,
@@ -1469,6 +1819,7 @@ impl<'fr, 'data> Value<'fr, 'data> {
     /// arguments and return its result, or catch the exception and throw a new one with two
     /// fields, `exc` and `stacktrace`, containing the original exception and the stacktrace
     /// respectively. This takes one slot on the GC stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn attach_stacktrace<'frame, F>(
         self,
         frame: &mut F,
@@ -1503,6 +1854,51 @@ impl Value<'_, '_> {
         jl_gc_add_finalizer(self.ptr(), f.ptr())
     }
 
+    /// Add a finalizer that calls the Rust closure `finalizer` when this value is about to be
+    /// freed by the garbage collector. Only mutable values can have finalizers, if this value is
+    /// immutable `JlrsError::Immutable` is returned. This temporarily takes three slots on the GC
+    /// stack.
+    ///
+    /// The finalizer is called by Julia while the GC is running or from the next safepoint,
+    /// possibly from another thread. It must not call into Julia or access Julia data, including
+    /// this value, and should only release the Rust resources it owns. If it panics, the panic
+    /// is caught and the finalizer is ignored. Finalizers that haven't been called when Julia
+    /// exits are called by [`Julia`]'s `Drop` implementation.
+    ///
+    /// [`Julia`]: ../struct.Julia.html
+    pub fn add_rust_finalizer<'frame, F, C>(self, frame: &mut F, finalizer: C) -> JlrsResult<()>
+    where
+        F: Frame<'frame>,
+        C: FnOnce() + Send + 'static,
+    {
+        if !self.datatype().map_or(false, |dt| dt.mutable()) {
+            Err(JlrsError::Immutable)?;
+        }
+
+        let finalizer: Box<Box<dyn FnOnce() + Send>> = Box::new(Box::new(finalizer));
+        let data = Box::into_raw(finalizer);
+
+        let res = frame.frame(3, |frame| unsafe {
+            let callback = Value::new(frame, call_rust_finalizer as *mut c_void)?;
+            let data = Value::new(frame, data.cast::<c_void>())?;
+
+            Module::main(Global::new())
+                .submodule("Jlrs")?
+                .function("addrustfinalizer")?
+                .call3(frame, self, callback, data)?
+                .map_err(|e| JlrsError::Exception(e.type_name().into()))?;
+
+            Ok(())
+        });
+
+        if res.is_err() {
+            // The finalizer hasn't been added, so it will never be called.
+            unsafe { drop(Box::from_raw(data)) };
+        }
+
+        res
+    }
+
     /// Call all finalizers.
     pub unsafe fn finalize(self) {
         jl_finalize(self.ptr())
@@ -1631,6 +2027,7 @@ impl<'func, 'funcdata, 'kw, 'kwdata> WithKeywords<'func, 'funcdata, 'kw, 'kwdata
     /// Call this function with keywords with no arguments, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call0<'frame, F>(self, frame: &mut F) -> JlrsResult<CallResult<'frame, 'static>>
     where
         F: Frame<'frame>,
@@ -1666,6 +2063,7 @@ impl<'func, 'funcdata, 'kw, 'kwdata> WithKeywords<'func, 'funcdata, 'kw, 'kwdata
     /// Call this function with keywords with one argument, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call1<'frame, 'borrow, F>(
         self,
         frame: &mut F,
@@ -1709,6 +2107,7 @@ impl<'func, 'funcdata, 'kw, 'kwdata> WithKeywords<'func, 'funcdata, 'kw, 'kwdata
     /// Call this function with keywords with two arguments, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call2<'frame, 'borrow, F>(
         self,
         frame: &mut F,
@@ -1754,6 +2153,7 @@ impl<'func, 'funcdata, 'kw, 'kwdata> WithKeywords<'func, 'funcdata, 'kw, 'kwdata
     /// Call this function with keywords with three arguments, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call3<'frame, 'borrow, F>(
         self,
         frame: &mut F,
@@ -1801,6 +2201,7 @@ impl<'func, 'funcdata, 'kw, 'kwdata> WithKeywords<'func, 'funcdata, 'kw, 'kwdata
     /// Call this function with keywords with several arguments, this takes one slot on the GC
     /// stack. Returns the result of this function call if no exception is thrown, the exception
     /// if one is, or an error if no space is left on the stack.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
     pub fn call<'frame, 'value, 'borrow, V, F>(
         self,
         frame: &mut F,
@@ -1965,6 +2366,31 @@ impl<'output, 'frame, 'data> WithOutput<'output, Value<'frame, 'data>> {
         }
     }
 
+    /// Call the value as a function that takes several arguments on another thread in Julia,
+    /// and use the `Output` to extend the result's lifetime. See [`Value::call_async`] for more
+    /// information. This takes two slots on the GC stack for the task, the result uses the slot
+    /// of the `Output`. This lets you return the result from a frame created with
+    /// [`AsyncFrame::async_frame`].
+    ///
+    /// [`Value::call_async`]: struct.Value.html#method.call_async
+    /// [`AsyncFrame::async_frame`]: ../frame/struct.AsyncFrame.html#method.async_frame
+    #[cfg(all(feature = "async", target_os = "linux"))]
+    pub async fn call_async<'value, 'borrow, 'fr, V>(
+        self,
+        frame: &mut crate::frame::AsyncFrame<'fr>,
+        args: &mut V,
+    ) -> JlrsResult<CallResult<'output, 'borrow>>
+    where
+        'borrow: 'output,
+        V: AsMut<[Value<'value, 'borrow>]>,
+    {
+        let res = crate::julia_future::JuliaFuture::new(frame, self.value, args)?.await;
+        match res {
+            Ok(v) => Ok(Ok(v.extend(frame, self.output))),
+            Err(e) => Ok(Err(e.extend(frame, self.output))),
+        }
+    }
+
     /// Call the value as a function that takes several arguments in a single `Values` and use
     /// the `Output` to extend the result's lifetime. This takes no space on the GC stack. Returns
     /// the result of this function call if no exception is thrown or the exception if one is.
@@ -2021,9 +2447,21 @@ where
     T: IntoJulia + JuliaType,
     D: Into<Dimensions>,
     F: Frame<'frame>,
+{
+    new_array_of_type(frame, T::julia_type().cast(), dimensions)
+}
+
+unsafe fn new_array_of_type<'frame, D, F>(
+    frame: &mut F,
+    elty: *mut jl_value_t,
+    dimensions: D,
+) -> JlrsResult<*mut jl_value_t>
+where
+    D: Into<Dimensions>,
+    F: Frame<'frame>,
 {
     let dims = dimensions.into();
-    let array_type = jl_apply_array_type(T::julia_type().cast(), dims.n_dimensions());
+    let array_type = jl_apply_array_type(elty, dims.n_dimensions());
 
     match dims.n_dimensions() {
         1 => Ok(jl_alloc_array_1d(array_type, dims.n_elements(0)).cast()),
@@ -2139,6 +2577,46 @@ where
     }
 }
 
+// The array must have been allocated with the type of `value` as its element type. No write
+// barrier is needed when the data is stored inline because the array has just been allocated.
+unsafe fn fill_array(array: *mut jl_value_t, value: Value) {
+    let arr: *mut jl_array_t = array.cast();
+    let len = jl_array_len(arr);
+
+    if (&*arr).flags.ptrarray() != 0 {
+        for i in 0..len {
+            jl_array_ptr_set(array.cast(), i, value.ptr().cast());
+        }
+    } else {
+        let elsize = (&*arr).elsize as usize;
+        let size = jl_datatype_size(jl_typeof(value.ptr()).cast()) as usize;
+        let src: *const u8 = value.ptr().cast();
+        let dest: *mut u8 = jl_array_data(array).cast();
+
+        for i in 0..len {
+            std::ptr::copy_nonoverlapping(src, dest.add(i * elsize), size);
+        }
+    }
+}
+
+// Elements that aren't stored inline are already initialized to `#undef`.
+unsafe fn zero_array(array: *mut jl_value_t) {
+    let arr: *mut jl_array_t = array.cast();
+
+    if (&*arr).flags.ptrarray() == 0 {
+        let n_bytes = jl_array_len(arr) * (&*arr).elsize as usize;
+        std::ptr::write_bytes(jl_array_data(array).cast::<u8>(), 0, n_bytes);
+    }
+}
+
+// This function is called by the finalizers added with `Value::add_rust_finalizer`.
+unsafe extern "C" fn call_rust_finalizer(data: *mut c_void) {
+    let finalizer = Box::from_raw(data.cast::<Box<dyn FnOnce() + Send>>());
+    // Unwinding into Julia is undefined behavior.
+    catch_unwind(AssertUnwindSafe(finalizer)).ok();
+}
+
+#[cfg_attr(feature = "debug-stack", track_caller)]
 unsafe fn try_protect<'frame, F>(
     frame: &mut F,
     res: *mut jl_value_t,
@@ -2178,6 +2656,7 @@ where
     }
 }
 
+#[cfg_attr(feature = "debug-stack", track_caller)]
 unsafe fn small_dim_tuple<'frame, F>(
     frame: &mut F,
     dims: &Dimensions,
@@ -2198,6 +2677,7 @@ where
     Ok(v)
 }
 
+#[cfg_attr(feature = "debug-stack", track_caller)]
 unsafe fn large_dim_tuple<'frame, F>(
     frame: &mut F,
     dims: &Dimensions,
@@ -13,14 +13,19 @@
 use crate::error::{JlrsError, JlrsResult};
 use crate::traits::{valid_layout::ValidLayout, Cast, Frame, JuliaTypecheck};
 use crate::value::datatype::DataType;
+use crate::value::value_ref::ValueRef;
 use crate::value::Value;
 use jl_sys::{
-    jl_array_data, jl_array_dim, jl_array_dims, jl_array_eltype, jl_array_ndims, jl_array_nrows,
-    jl_array_ptr_set, jl_array_t, jl_is_array_type, jl_tparam0, jl_typeof,
+    jl_array_data, jl_array_data_owner, jl_array_del_at, jl_array_del_end, jl_array_dim,
+    jl_array_dims, jl_array_eltype, jl_array_grow_at, jl_array_grow_end, jl_array_len,
+    jl_array_ndims, jl_array_nrows, jl_array_ptr_set, jl_array_sizehint, jl_array_t, jl_arrayset,
+    jl_astaggedvalue, jl_gc_queue_root, jl_is_array_type, jl_subtype, jl_tparam0, jl_typeof,
 };
 use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::iter::Zip;
 use std::marker::PhantomData;
-use std::ops::{Index, IndexMut};
+use std::ops::{Index, IndexMut, Range};
+use std::slice::IterMut;
 
 /// An n-dimensional Julia array. This struct implements [`JuliaTypecheck`] and [`Cast`]. It can
 /// be used in combination with [`DataType::is`] and [`Value::is`]; if the check returns `true`
@@ -61,6 +66,10 @@ use std::ops::{Index, IndexMut};
 /// If the data isn't inlined each element is stored as a [`Value`]. This data can be accessed
 /// using [`Array::value_data`] and [`Array::value_data_mut`] but this is unsafe.
 ///
+/// One-dimensional arrays can be resized with methods like [`Array::push`] and
+/// [`Array::delete_at`], unless they share their data with another array or borrow it from
+/// Rust.
+///
 /// [`JuliaTypecheck`]: ../../traits/trait.JuliaTypecheck.html
 /// [`Cast`]: ../../traits/trait.Cast.html
 /// [`DataType::is`]: ../datatype/struct.DataType.html#method.is
@@ -76,6 +85,8 @@ use std::ops::{Index, IndexMut};
 /// [`JuliaStruct`]: ../../traits/trait.JuliaStruct.html
 /// [`Array::value_data`]: struct.Array.html#method.value_data
 /// [`Array::value_data_mut`]: struct.Array.html#method.value_data_mut
+/// [`Array::push`]: struct.Array.html#method.push
+/// [`Array::delete_at`]: struct.Array.html#method.delete_at
 #[derive(Copy, Clone, Debug)]
 #[repr(transparent)]
 pub struct Array<'frame, 'data>(
@@ -283,6 +294,32 @@ impl<'frame, 'data> Array<'frame, 'data> {
         Ok(ArrayData::new(data, dimensions, frame))
     }
 
+    /// Immutably borrow the elements of this value array as [`ValueRef`]s, you can borrow data
+    /// from multiple arrays at the same time. Unlike [`Array::value_data`] the elements can't be
+    /// used as a `Value` directly, a `ValueRef` must be rooted before it can be used as one.
+    /// Returns `JlrsError::Inline` if the data is stored inline.
+    ///
+    /// [`ValueRef`]: ../value_ref/struct.ValueRef.html
+    /// [`Array::value_data`]: struct.Array.html#method.value_data
+    pub fn value_refs<'borrow, 'fr, F>(
+        self,
+        frame: &'borrow F,
+    ) -> JlrsResult<ArrayData<'borrow, 'fr, ValueRef<'frame, 'data>, F>>
+    where
+        F: Frame<'fr>,
+    {
+        if !self.is_value_array() {
+            Err(JlrsError::Inline)?;
+        }
+
+        unsafe {
+            let jl_data = jl_array_data(self.ptr().cast()).cast();
+            let dimensions = Dimensions::from_array(self.ptr().cast());
+            let data = std::slice::from_raw_parts(jl_data, dimensions.size());
+            Ok(ArrayData::new(data, dimensions, frame))
+        }
+    }
+
     /// Mutably borrow the data of this value array, you can mutably borrow a single array at the
     /// same time. Returns `JlrsError::Inline` if the data is stored inline.
     ///
@@ -339,6 +376,159 @@ impl<'frame, 'data> Array<'frame, 'data> {
         ))
     }
 
+    /// Add `value` to the end of this vector. Returns `JlrsError::InvalidArrayType` if the type
+    /// of `value` is not a subtype of the element type, or an error if this array can't be
+    /// resized. Only one-dimensional arrays that don't share their data with another array and
+    /// that don't borrow their data from Rust can be resized.
+    ///
+    /// The frame is mutably borrowed to ensure the data of this array isn't borrowed while it's
+    /// resized.
+    pub fn push<'fr, 'da, F>(self, _frame: &mut F, value: Value<'_, 'da>) -> JlrsResult<()>
+    where
+        'da: 'data,
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            check_element_type(self.ptr(), value)?;
+
+            let len = jl_array_len(self.ptr());
+            jl_array_grow_end(self.ptr(), 1);
+            jl_arrayset(self.ptr(), value.ptr(), len);
+        }
+
+        Ok(())
+    }
+
+    /// Add all `values` to the end of this vector. Returns `JlrsError::InvalidArrayType` if the
+    /// type of one of the values is not a subtype of the element type, or an error if this array
+    /// can't be resized.
+    pub fn extend_from_slice<'fr, 'da, F>(
+        self,
+        _frame: &mut F,
+        values: &[Value<'_, 'da>],
+    ) -> JlrsResult<()>
+    where
+        'da: 'data,
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            for value in values.iter().copied() {
+                check_element_type(self.ptr(), value)?;
+            }
+
+            let len = jl_array_len(self.ptr());
+            jl_array_grow_end(self.ptr(), values.len());
+            for (i, value) in values.iter().enumerate() {
+                jl_arrayset(self.ptr(), value.ptr(), len + i);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insert `value` at position `index` in this vector, all elements after it are shifted to
+    /// the right. Returns `JlrsError::OutOfBounds` if `index` is larger than the length of this
+    /// vector, `JlrsError::InvalidArrayType` if the type of `value` is not a subtype of the
+    /// element type, or an error if this array can't be resized.
+    pub fn insert<'fr, 'da, F>(
+        self,
+        _frame: &mut F,
+        index: usize,
+        value: Value<'_, 'da>,
+    ) -> JlrsResult<()>
+    where
+        'da: 'data,
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            check_insert_index(self.ptr(), index)?;
+            check_element_type(self.ptr(), value)?;
+
+            jl_array_grow_at(self.ptr(), index as isize, 1);
+            jl_arrayset(self.ptr(), value.ptr(), index);
+        }
+
+        Ok(())
+    }
+
+    /// Remove the element at position `index` from this vector, all elements after it are
+    /// shifted to the left. Returns `JlrsError::OutOfBounds` if `index` is not a valid index, or
+    /// an error if this array can't be resized.
+    pub fn delete_at<'fr, F>(self, _frame: &mut F, index: usize) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            check_delete_index(self.ptr(), index)?;
+            jl_array_del_at(self.ptr(), index as isize, 1);
+        }
+
+        Ok(())
+    }
+
+    /// Resize this vector to `len` elements. If the vector grows, the new elements are set to
+    /// `value`. Returns `JlrsError::InvalidArrayType` if the type of `value` is not a subtype of
+    /// the element type, or an error if this array can't be resized.
+    pub fn resize<'fr, 'da, F>(
+        self,
+        _frame: &mut F,
+        len: usize,
+        value: Value<'_, 'da>,
+    ) -> JlrsResult<()>
+    where
+        'da: 'data,
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+
+            let old_len = jl_array_len(self.ptr());
+            if len <= old_len {
+                jl_array_del_end(self.ptr(), old_len - len);
+            } else {
+                check_element_type(self.ptr(), value)?;
+                jl_array_grow_end(self.ptr(), len - old_len);
+                for i in old_len..len {
+                    jl_arrayset(self.ptr(), value.ptr(), i);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shorten this vector to `len` elements. Nothing happens if the vector contains `len` or
+    /// fewer elements. Returns an error if this array can't be resized.
+    pub fn truncate<'fr, F>(self, _frame: &mut F, len: usize) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            truncate(self.ptr(), len);
+        }
+
+        Ok(())
+    }
+
+    /// Suggest that this vector reserves capacity for at least `capacity` elements, like
+    /// `Base.sizehint!`. Returns an error if this array can't be resized.
+    pub fn sizehint<'fr, F>(self, _frame: &mut F, capacity: usize) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            jl_array_sizehint(self.ptr(), capacity);
+        }
+
+        Ok(())
+    }
+
     /// Convert `self` to a `Value`.
     pub fn as_value(self) -> Value<'frame, 'data> {
         self.into()
@@ -555,6 +745,32 @@ impl<'frame, 'data, T: Copy + ValidLayout> TypedArray<'frame, 'data, T> {
         Ok(ArrayData::new(data, dimensions, frame))
     }
 
+    /// Immutably borrow the elements of this value array as [`ValueRef`]s, you can borrow data
+    /// from multiple arrays at the same time. Unlike [`Array::value_data`] the elements can't be
+    /// used as a `Value` directly, a `ValueRef` must be rooted before it can be used as one.
+    /// Returns `JlrsError::Inline` if the data is stored inline.
+    ///
+    /// [`ValueRef`]: ../value_ref/struct.ValueRef.html
+    /// [`Array::value_data`]: struct.Array.html#method.value_data
+    pub fn value_refs<'borrow, 'fr, F>(
+        self,
+        frame: &'borrow F,
+    ) -> JlrsResult<ArrayData<'borrow, 'fr, ValueRef<'frame, 'data>, F>>
+    where
+        F: Frame<'fr>,
+    {
+        if !self.is_value_array() {
+            Err(JlrsError::Inline)?;
+        }
+
+        unsafe {
+            let jl_data = jl_array_data(self.ptr().cast()).cast();
+            let dimensions = Dimensions::from_array(self.ptr().cast());
+            let data = std::slice::from_raw_parts(jl_data, dimensions.size());
+            Ok(ArrayData::new(data, dimensions, frame))
+        }
+    }
+
     /// Mutably borrow the data of this value array, you can mutably borrow a single array at the
     /// same time. Returns `JlrsError::Inline` if the data is stored inline.
     ///
@@ -614,6 +830,149 @@ impl<'frame, 'data, T: Copy + ValidLayout> TypedArray<'frame, 'data, T> {
         ))
     }
 
+    /// Add `value` to the end of this vector. Returns `JlrsError::NotInline` if the data is not
+    /// stored inline, or an error if this array can't be resized. Only one-dimensional arrays
+    /// that don't share their data with another array and that don't borrow their data from Rust
+    /// can be resized.
+    ///
+    /// The frame is mutably borrowed to ensure the data of this array isn't borrowed while it's
+    /// resized.
+    pub fn push<'fr, F>(self, _frame: &mut F, value: T) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            if !self.is_inline_array() {
+                Err(JlrsError::NotInline)?;
+            }
+
+            let len = jl_array_len(self.ptr());
+            jl_array_grow_end(self.ptr(), 1);
+            write_inline(self.ptr(), len, value);
+            inline_write_barrier(self.ptr());
+        }
+
+        Ok(())
+    }
+
+    /// Add all `values` to the end of this vector. Returns `JlrsError::NotInline` if the data is
+    /// not stored inline, or an error if this array can't be resized.
+    pub fn extend_from_slice<'fr, F>(self, _frame: &mut F, values: &[T]) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            if !self.is_inline_array() {
+                Err(JlrsError::NotInline)?;
+            }
+
+            let len = jl_array_len(self.ptr());
+            jl_array_grow_end(self.ptr(), values.len());
+            let data: *mut T = jl_array_data(self.ptr().cast()).cast();
+            std::ptr::copy_nonoverlapping(values.as_ptr(), data.add(len), values.len());
+            inline_write_barrier(self.ptr());
+        }
+
+        Ok(())
+    }
+
+    /// Insert `value` at position `index` in this vector, all elements after it are shifted to
+    /// the right. Returns `JlrsError::OutOfBounds` if `index` is larger than the length of this
+    /// vector, `JlrsError::NotInline` if the data is not stored inline, or an error if this array
+    /// can't be resized.
+    pub fn insert<'fr, F>(self, _frame: &mut F, index: usize, value: T) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            check_insert_index(self.ptr(), index)?;
+            if !self.is_inline_array() {
+                Err(JlrsError::NotInline)?;
+            }
+
+            jl_array_grow_at(self.ptr(), index as isize, 1);
+            write_inline(self.ptr(), index, value);
+            inline_write_barrier(self.ptr());
+        }
+
+        Ok(())
+    }
+
+    /// Remove the element at position `index` from this vector, all elements after it are
+    /// shifted to the left. Returns `JlrsError::OutOfBounds` if `index` is not a valid index, or
+    /// an error if this array can't be resized.
+    pub fn delete_at<'fr, F>(self, _frame: &mut F, index: usize) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            check_delete_index(self.ptr(), index)?;
+            jl_array_del_at(self.ptr(), index as isize, 1);
+        }
+
+        Ok(())
+    }
+
+    /// Resize this vector to `len` elements. If the vector grows, the new elements are set to
+    /// `value`. Returns `JlrsError::NotInline` if the data is not stored inline, or an error if
+    /// this array can't be resized.
+    pub fn resize<'fr, F>(self, _frame: &mut F, len: usize, value: T) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            if !self.is_inline_array() {
+                Err(JlrsError::NotInline)?;
+            }
+
+            let old_len = jl_array_len(self.ptr());
+            if len <= old_len {
+                jl_array_del_end(self.ptr(), old_len - len);
+            } else {
+                jl_array_grow_end(self.ptr(), len - old_len);
+                for i in old_len..len {
+                    write_inline(self.ptr(), i, value);
+                }
+                inline_write_barrier(self.ptr());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shorten this vector to `len` elements. Nothing happens if the vector contains `len` or
+    /// fewer elements. Returns an error if this array can't be resized.
+    pub fn truncate<'fr, F>(self, _frame: &mut F, len: usize) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            truncate(self.ptr(), len);
+        }
+
+        Ok(())
+    }
+
+    /// Suggest that this vector reserves capacity for at least `capacity` elements, like
+    /// `Base.sizehint!`. Returns an error if this array can't be resized.
+    pub fn sizehint<'fr, F>(self, _frame: &mut F, capacity: usize) -> JlrsResult<()>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            check_resizable(self.ptr())?;
+            jl_array_sizehint(self.ptr(), capacity);
+        }
+
+        Ok(())
+    }
+
     /// Convert `self` to a `Value`.
     pub fn as_value(self) -> Value<'frame, 'data> {
         self.into()
@@ -720,6 +1079,23 @@ impl<T> CopiedArray<T> {
     pub fn dimensions(&self) -> &Dimensions {
         &self.dimensions
     }
+
+    /// Returns an immutable view of the whole array. The view can be used to iterate over the
+    /// elements and their indices, and to access parts of the array; see [`ArrayView`] for
+    /// more information.
+    ///
+    /// [`ArrayView`]: struct.ArrayView.html
+    pub fn as_view(&self) -> ArrayView<'_, T> {
+        ArrayView::new(&self.data, &self.dimensions)
+    }
+
+    /// Returns a mutable view of the whole array. The view can be used to modify the elements
+    /// while iterating over them and their indices; see [`ArrayViewMut`] for more information.
+    ///
+    /// [`ArrayViewMut`]: struct.ArrayViewMut.html
+    pub fn as_view_mut(&mut self) -> ArrayViewMut<'_, T> {
+        ArrayViewMut::new(&mut self.data, &self.dimensions)
+    }
 }
 
 impl<T, D: Into<Dimensions>> Index<D> for CopiedArray<T> {
@@ -783,6 +1159,15 @@ where
     pub fn dimensions(&self) -> &Dimensions {
         &self.dimensions
     }
+
+    /// Returns an immutable view of the whole array. The view can be used to iterate over the
+    /// elements and their indices, and to access parts of the array; see [`ArrayView`] for
+    /// more information.
+    ///
+    /// [`ArrayView`]: struct.ArrayView.html
+    pub fn as_view(&self) -> ArrayView<'_, T> {
+        ArrayView::new(self.data, &self.dimensions)
+    }
 }
 
 impl<'borrow, 'frame, T, D, F> Index<D> for ArrayData<'borrow, 'frame, T, F>
@@ -861,6 +1246,23 @@ where
     pub fn dimensions(&self) -> &Dimensions {
         &self.dimensions
     }
+
+    /// Returns an immutable view of the whole array. The view can be used to iterate over the
+    /// elements and their indices, and to access parts of the array; see [`ArrayView`] for
+    /// more information.
+    ///
+    /// [`ArrayView`]: struct.ArrayView.html
+    pub fn as_view(&self) -> ArrayView<'_, T> {
+        ArrayView::new(self.data, &self.dimensions)
+    }
+
+    /// Returns a mutable view of the whole array. The view can be used to modify the elements
+    /// while iterating over them and their indices; see [`ArrayViewMut`] for more information.
+    ///
+    /// [`ArrayViewMut`]: struct.ArrayViewMut.html
+    pub fn as_view_mut(&mut self) -> ArrayViewMut<'_, T> {
+        ArrayViewMut::new(self.data, &self.dimensions)
+    }
 }
 
 /// Mutably borrowed value array data from Julia. The data has a column-major order and can be
@@ -944,6 +1346,23 @@ where
     pub fn dimensions(&self) -> &Dimensions {
         &self.dimensions
     }
+
+    /// Returns an immutable view of the whole array. The view can be used to iterate over the
+    /// elements and their indices, and to access parts of the array; see [`ArrayView`] for
+    /// more information.
+    ///
+    /// [`ArrayView`]: struct.ArrayView.html
+    pub fn as_view(&self) -> ArrayView<'_, T> {
+        ArrayView::new(self.data, &self.dimensions)
+    }
+
+    /// Returns a mutable view of the whole array. The view can be used to modify the elements
+    /// while iterating over them and their indices; see [`ArrayViewMut`] for more information.
+    ///
+    /// [`ArrayViewMut`]: struct.ArrayViewMut.html
+    pub fn as_view_mut(&mut self) -> ArrayViewMut<'_, T> {
+        ArrayViewMut::new(self.data, &self.dimensions)
+    }
 }
 
 /// Mutably borrowed value array data from Julia. The data has a column-major order and can be
@@ -1033,6 +1452,15 @@ where
     pub fn dimensions(&self) -> &Dimensions {
         &self.dimensions
     }
+
+    /// Returns an immutable view of the whole array. The view can be used to iterate over the
+    /// elements and their indices, and to access parts of the array; see [`ArrayView`] for
+    /// more information.
+    ///
+    /// [`ArrayView`]: struct.ArrayView.html
+    pub fn as_view(&self) -> ArrayView<'_, Value<'frame, 'data>> {
+        ArrayView::new(self.data, &self.dimensions)
+    }
 }
 
 impl<'borrow, 'value, 'data, 'frame, D, F> Index<D>
@@ -1110,6 +1538,15 @@ where
     pub fn dimensions(&self) -> &Dimensions {
         &self.dimensions
     }
+
+    /// Returns an immutable view of the whole array. The view can be used to iterate over the
+    /// elements and their indices, and to access parts of the array; see [`ArrayView`] for
+    /// more information.
+    ///
+    /// [`ArrayView`]: struct.ArrayView.html
+    pub fn as_view(&self) -> ArrayView<'_, Value<'frame, 'data>> {
+        ArrayView::new(self.data, &self.dimensions)
+    }
 }
 
 impl<'borrow, 'value, 'data, 'frame, D, F> Index<D>
@@ -1124,12 +1561,336 @@ where
     }
 }
 
+/// An immutable view of part of an n-dimensional array. The elements of the view don't need to
+/// be stored contiguously, but like the array itself a view is indexed in column-major order.
+/// A view of the whole array can be created by calling `as_view` on borrowed or copied array
+/// data.
+#[derive(Clone)]
+pub struct ArrayView<'borrow, T> {
+    data: &'borrow [T],
+    offset: usize,
+    dimensions: Dimensions,
+    strides: Box<[usize]>,
+}
+
+impl<'borrow, T> ArrayView<'borrow, T> {
+    pub(crate) fn new(data: &'borrow [T], dimensions: &Dimensions) -> Self {
+        let mut stride = 1;
+        let strides = dimensions
+            .as_slice()
+            .iter()
+            .map(|n| {
+                let s = stride;
+                stride *= n;
+                s
+            })
+            .collect();
+
+        ArrayView {
+            data,
+            offset: 0,
+            dimensions: dimensions.clone(),
+            strides,
+        }
+    }
+
+    /// Get a reference to the value at `index`, or `None` if the index is out of bounds.
+    pub fn get<D: Into<Dimensions>>(&self, index: D) -> Option<&'borrow T> {
+        let idx = self.linear_index(&index.into())?;
+        self.data.get(idx)
+    }
+
+    /// Returns a reference to the view's dimensions.
+    pub fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    /// Returns an iterator over the elements of the view in column-major order.
+    pub fn iter(&self) -> impl Iterator<Item = &'borrow T> + '_ {
+        self.indexed_iter().map(|(_, elem)| elem)
+    }
+
+    /// Returns an iterator over the elements of the view in column-major order, together with
+    /// their n-dimensional index in the view.
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Dimensions, &'borrow T)> + '_ {
+        self.dimensions.indices().map(move |index| {
+            let idx = self.linear_index(&index).unwrap();
+            (index, &self.data[idx])
+        })
+    }
+
+    /// Returns an immutable view of part of this view, `ranges` must contain a range for each
+    /// dimension. Returns `JlrsError::InvalidView` if the ranges are out of bounds.
+    pub fn view<R: AsRef<[Range<usize>]>>(&self, ranges: R) -> JlrsResult<ArrayView<'borrow, T>> {
+        let ranges = ranges.as_ref();
+        let shape = self.dimensions.as_slice();
+
+        if ranges.len() != shape.len()
+            || ranges
+                .iter()
+                .zip(shape)
+                .any(|(range, &n)| range.start > range.end || range.end > n)
+        {
+            Err(JlrsError::InvalidView(
+                ranges.to_vec(),
+                self.dimensions.clone(),
+            ))?;
+        }
+
+        let offset = ranges
+            .iter()
+            .zip(self.strides.iter())
+            .fold(self.offset, |offset, (range, stride)| {
+                offset + range.start * stride
+            });
+        let dims: Vec<_> = ranges.iter().map(|range| range.end - range.start).collect();
+
+        Ok(ArrayView {
+            data: self.data,
+            offset,
+            dimensions: Dimensions::from_slice(&dims),
+            strides: self.strides.clone(),
+        })
+    }
+
+    /// Returns an iterator over the subviews along `axis`, each subview has one dimension less
+    /// than this view. For example, if this view is a matrix the columns are returned if `axis`
+    /// is 1. Returns `JlrsError::InvalidAxis` if `axis` is not a valid dimension.
+    pub fn axis_iter(&self, axis: usize) -> JlrsResult<AxisIter<'borrow, T>> {
+        let n_dims = self.dimensions.n_dimensions();
+        if axis >= n_dims {
+            Err(JlrsError::InvalidAxis(axis, n_dims))?;
+        }
+
+        let mut dims = self.dimensions.as_slice().to_vec();
+        let mut strides = self.strides.to_vec();
+        let len = dims.remove(axis);
+        let stride = strides.remove(axis);
+
+        Ok(AxisIter {
+            data: self.data,
+            offset: self.offset,
+            stride,
+            current: 0,
+            len,
+            dimensions: Dimensions::from_slice(&dims),
+            strides: strides.into_boxed_slice(),
+        })
+    }
+
+    fn linear_index(&self, index: &Dimensions) -> Option<usize> {
+        let index = index.as_slice();
+        let shape = self.dimensions.as_slice();
+
+        if index.len() != shape.len() {
+            return None;
+        }
+
+        let mut offset = self.offset;
+        for ((&idx, &n), stride) in index.iter().zip(shape).zip(self.strides.iter()) {
+            if idx >= n {
+                return None;
+            }
+
+            offset += idx * stride;
+        }
+
+        Some(offset)
+    }
+}
+
+impl<'borrow, T, D> Index<D> for ArrayView<'borrow, T>
+where
+    D: Into<Dimensions>,
+{
+    type Output = T;
+    fn index(&self, index: D) -> &Self::Output {
+        let idx = self
+            .linear_index(&index.into())
+            .expect("Index out of bounds");
+        &self.data[idx]
+    }
+}
+
+/// A mutable view of a whole n-dimensional array, created by calling `as_view_mut` on copied or
+/// mutably borrowed inline array data. Like the array itself, a view is indexed in column-major
+/// order.
+pub struct ArrayViewMut<'borrow, T> {
+    data: &'borrow mut [T],
+    dimensions: Dimensions,
+}
+
+impl<'borrow, T> ArrayViewMut<'borrow, T> {
+    pub(crate) fn new(data: &'borrow mut [T], dimensions: &Dimensions) -> Self {
+        ArrayViewMut {
+            data,
+            dimensions: dimensions.clone(),
+        }
+    }
+
+    /// Get a mutable reference to the value at `index`, or `None` if the index is out of bounds.
+    pub fn get_mut<D: Into<Dimensions>>(&mut self, index: D) -> Option<&mut T> {
+        Some(&mut self.data[self.dimensions.index_of(index).ok()?])
+    }
+
+    /// Returns a reference to the view's dimensions.
+    pub fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    /// Returns an iterator that allows modifying the elements of the view, in column-major
+    /// order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.data.iter_mut()
+    }
+
+    /// Returns an iterator that allows modifying the elements of the view, in column-major
+    /// order, together with their n-dimensional index.
+    pub fn indexed_iter_mut(&mut self) -> Zip<Indices, IterMut<'_, T>> {
+        self.dimensions.indices().zip(self.data.iter_mut())
+    }
+}
+
+/// An iterator over the subviews of an array along some axis, created by calling `axis_iter`.
+pub struct AxisIter<'borrow, T> {
+    data: &'borrow [T],
+    offset: usize,
+    stride: usize,
+    current: usize,
+    len: usize,
+    dimensions: Dimensions,
+    strides: Box<[usize]>,
+}
+
+impl<'borrow, T> Iterator for AxisIter<'borrow, T> {
+    type Item = ArrayView<'borrow, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current == self.len {
+            return None;
+        }
+
+        let view = ArrayView {
+            data: self.data,
+            offset: self.offset + self.current * self.stride,
+            dimensions: self.dimensions.clone(),
+            strides: self.strides.clone(),
+        };
+
+        self.current += 1;
+        Some(view)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.current;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'borrow, T> ExactSizeIterator for AxisIter<'borrow, T> {}
+
+// Julia throws an exception if an array that can't be resized is resized anyway, these cases
+// must be caught before any of the resizing functions is called. Arrays created with
+// `Value::borrow_array` and `Value::move_array` are marked as shared, but so are arrays whose
+// buffer is shared with another array, e.g. after calling `reshape`. An array borrows its data
+// from Rust if Julia doesn't own its buffer and the data isn't stored inline.
+unsafe fn check_resizable(array: *mut jl_array_t) -> JlrsResult<()> {
+    let flags = (&*array).flags;
+
+    if flags.ndims() != 1 {
+        Err(JlrsError::NotAVector(flags.ndims() as usize))?;
+    }
+
+    if flags.isshared() != 0 {
+        if flags.how() == 0 && !has_inline_buffer(array) {
+            Err(JlrsError::BorrowedArray)?;
+        }
+
+        Err(JlrsError::SharedArray)?;
+    }
+
+    Ok(())
+}
+
+// Small arrays of bits-types are allocated together with their data, which is stored directly
+// after the header aligned to the cache line size. Deleting elements from the start of an array
+// moves the data pointer by `offset` elements.
+unsafe fn has_inline_buffer(array: *mut jl_array_t) -> bool {
+    const CACHE_BYTE_ALIGNMENT: usize = 64;
+
+    let arr = &*array;
+    let ndims = arr.flags.ndims() as usize;
+    let ndimwords = if ndims < 3 { 0 } else { ndims - 2 };
+    let header = std::mem::size_of::<jl_array_t>() + ndimwords * std::mem::size_of::<usize>();
+    let data_offset = (header + CACHE_BYTE_ALIGNMENT - 1) & !(CACHE_BYTE_ALIGNMENT - 1);
+    let inline_data = array as usize + data_offset;
+
+    arr.data as usize == inline_data + arr.offset as usize * arr.elsize as usize
+}
+
+unsafe fn check_element_type(array: *mut jl_array_t, value: Value) -> JlrsResult<()> {
+    let eltype = jl_array_eltype(array.cast());
+    if jl_subtype(jl_typeof(value.ptr()), eltype.cast()) == 0 {
+        Err(JlrsError::InvalidArrayType)?;
+    }
+
+    Ok(())
+}
+
+unsafe fn check_insert_index(array: *mut jl_array_t, index: usize) -> JlrsResult<()> {
+    let len = jl_array_len(array);
+    if index > len {
+        Err(JlrsError::OutOfBounds(index, len))?;
+    }
+
+    Ok(())
+}
+
+unsafe fn check_delete_index(array: *mut jl_array_t, index: usize) -> JlrsResult<()> {
+    let len = jl_array_len(array);
+    if index >= len {
+        Err(JlrsError::OutOfBounds(index, len))?;
+    }
+
+    Ok(())
+}
+
+unsafe fn truncate(array: *mut jl_array_t, len: usize) {
+    let old_len = jl_array_len(array);
+    if len < old_len {
+        jl_array_del_end(array, old_len - len);
+    }
+}
+
+unsafe fn write_inline<T>(array: *mut jl_array_t, index: usize, value: T) {
+    let data: *mut T = jl_array_data(array.cast()).cast();
+    data.add(index).write(value);
+}
+
+// Inline elements can contain pointers to other values. If an old array is updated with such
+// elements, it must be added to the remembered set.
+unsafe fn inline_write_barrier(array: *mut jl_array_t) {
+    if (&*array).flags.hasptr() == 0 {
+        return;
+    }
+
+    let owner = if (&*array).flags.how() == 3 {
+        jl_array_data_owner(array)
+    } else {
+        array.cast()
+    };
+
+    if (&*jl_astaggedvalue(owner)).__bindgen_anon_1.bits.gc() == 3 {
+        jl_gc_queue_root(owner);
+    }
+}
+
 /// The dimensions of an n-dimensional array, they represent either the shape of an array or an
 /// index. Functions that need `Dimensions` as an input, which is currently limited to just
 /// indexing this data, are generic and accept any type that implements `Into<Dimensions>`.
 ///
 /// For a single dimension, you can use a `usize` value. For 0 up to and including 8 dimensions,
-/// you can use tuples of `usize`. In general, you can use slices of `usize`:
+/// you can use tuples of `usize`. In general, you can use arrays, slices and vectors of `usize`:
 ///
 /// ```
 /// # use jlrs::value::array::Dimensions;
@@ -1139,6 +1900,8 @@ where
 /// let _1d_tuple: Dimensions = (42,).into();
 /// let _2d: Dimensions = (42, 6).into();
 /// let _nd: Dimensions = [42, 6, 12, 3].as_ref().into();
+/// let _nd_array: Dimensions = [42, 6, 12, 3].into();
+/// let _nd_vec: Dimensions = vec![42, 6, 12, 3].into();
 /// # }
 /// ```
 #[derive(Clone)]
@@ -1222,6 +1985,16 @@ impl Dimensions {
         Ok(idx)
     }
 
+    /// Returns an iterator over all valid indices for an array of shape `self`, in column-major
+    /// order.
+    pub fn indices(&self) -> Indices {
+        Indices {
+            shape: self.clone(),
+            current: vec![0; self.n_dimensions()],
+            remaining: self.size(),
+        }
+    }
+
     /// Returns the raw dimensions as a slice.
     pub fn as_slice(&self) -> &[usize] {
         match self {
@@ -1230,13 +2003,23 @@ impl Dimensions {
         }
     }
 
+    fn from_slice(dims: &[usize]) -> Self {
+        match dims.len() {
+            0 => Into::into(()),
+            1 => Into::into(dims[0]),
+            2 => Into::into((dims[0], dims[1])),
+            3 => Into::into((dims[0], dims[1], dims[2])),
+            _ => Into::into(dims),
+        }
+    }
+
     fn check_bounds(&self, dim_index: &Dimensions) -> JlrsResult<()> {
         if self.n_dimensions() != dim_index.n_dimensions() {
             Err(JlrsError::InvalidIndex(dim_index.clone(), self.clone()))?;
         }
 
         for i in 0..self.n_dimensions() {
-            if self.n_elements(i) < dim_index.n_elements(i) {
+            if self.n_elements(i) <= dim_index.n_elements(i) {
                 Err(JlrsError::InvalidIndex(dim_index.clone(), self.clone()))?;
             }
         }
@@ -1269,6 +2052,46 @@ impl Display for Dimensions {
     }
 }
 
+/// An iterator over all indices of an array in column-major order, created by calling
+/// [`Dimensions::indices`].
+///
+/// [`Dimensions::indices`]: struct.Dimensions.html#method.indices
+pub struct Indices {
+    shape: Dimensions,
+    current: Vec<usize>,
+    remaining: usize,
+}
+
+impl Iterator for Indices {
+    type Item = Dimensions;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let index = Dimensions::from_slice(&self.current);
+        self.remaining -= 1;
+
+        for (idx, &n) in self.current.iter_mut().zip(self.shape.as_slice()) {
+            *idx += 1;
+            if *idx < n {
+                break;
+            }
+
+            *idx = 0;
+        }
+
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Indices {}
+
 impl Into<Dimensions> for usize {
     fn into(self) -> Dimensions {
         Dimensions::Few([1, self, 0, 0])
@@ -1345,9 +2168,21 @@ impl Into<Dimensions> for &[usize] {
     }
 }
 
+impl<const N: usize> From<[usize; N]> for Dimensions {
+    fn from(dims: [usize; N]) -> Self {
+        Dimensions::from_slice(&dims)
+    }
+}
+
+impl From<Vec<usize>> for Dimensions {
+    fn from(dims: Vec<usize>) -> Self {
+        Dimensions::from_slice(&dims)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Dimensions;
+    use super::{ArrayView, Dimensions};
     #[test]
     fn convert_usize() {
         let d: Dimensions = 4.into();
@@ -1466,4 +2301,91 @@ mod tests {
         assert_eq!(d.n_elements(2), 3);
         assert_eq!(d.size(), 6);
     }
+
+    #[test]
+    fn convert_array() {
+        let d: Dimensions = [1, 2, 3, 4].into();
+        assert_eq!(d.n_dimensions(), 4);
+        assert_eq!(d.as_slice(), &[1, 2, 3, 4]);
+        assert_eq!(d.size(), 24);
+    }
+
+    #[test]
+    fn convert_vec() {
+        let d: Dimensions = vec![3, 2].into();
+        assert_eq!(d.n_dimensions(), 2);
+        assert_eq!(d.as_slice(), &[3, 2]);
+        assert_eq!(d.size(), 6);
+    }
+
+    #[test]
+    fn index_out_of_bounds() {
+        let d: Dimensions = (2, 3).into();
+        assert_eq!(d.index_of((1, 2)).unwrap(), 5);
+        assert!(d.index_of((2, 0)).is_err());
+        assert!(d.index_of((0, 3)).is_err());
+    }
+
+    #[test]
+    fn indices_column_major() {
+        let d: Dimensions = (2, 3).into();
+        let indices: Vec<Vec<usize>> = d.indices().map(|i| i.as_slice().to_vec()).collect();
+        assert_eq!(
+            indices,
+            vec![
+                vec![0, 0],
+                vec![1, 0],
+                vec![0, 1],
+                vec![1, 1],
+                vec![0, 2],
+                vec![1, 2]
+            ]
+        );
+
+        for (linear, index) in d.indices().enumerate() {
+            assert_eq!(d.index_of(index).unwrap(), linear);
+        }
+    }
+
+    #[test]
+    fn view_of_matrix() {
+        let data: Vec<usize> = (0..12).collect();
+        let dims: Dimensions = (3, 4).into();
+        let view = ArrayView::new(&data, &dims);
+
+        let sub = view.view([1..3, 1..3]).unwrap();
+        assert_eq!(sub.dimensions().as_slice(), &[2, 2]);
+        assert_eq!(sub.iter().copied().collect::<Vec<_>>(), vec![4, 5, 7, 8]);
+        assert_eq!(sub[(1, 1)], 8);
+        assert!(sub.get((2, 0)).is_none());
+
+        let subsub = sub.view([1..2, 0..2]).unwrap();
+        assert_eq!(subsub.iter().copied().collect::<Vec<_>>(), vec![5, 8]);
+
+        assert!(view.view([0..4, 0..1]).is_err());
+        assert!(view.view([0..1, 0..1, 0..1]).is_err());
+    }
+
+    #[test]
+    fn axis_iter_of_matrix() {
+        let data: Vec<usize> = (0..6).collect();
+        let dims: Dimensions = (2, 3).into();
+        let view = ArrayView::new(&data, &dims);
+
+        let columns: Vec<Vec<usize>> = view
+            .axis_iter(1)
+            .unwrap()
+            .map(|col| col.iter().copied().collect())
+            .collect();
+        assert_eq!(columns, vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+
+        let rows: Vec<Vec<usize>> = view
+            .axis_iter(0)
+            .unwrap()
+            .map(|row| row.iter().copied().collect())
+            .collect();
+        assert_eq!(rows, vec![vec![0, 2, 4], vec![1, 3, 5]]);
+
+        assert!(view.axis_iter(2).is_err());
+    }
 }
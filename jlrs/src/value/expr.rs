@@ -1,12 +1,43 @@
 //! Support for values with the `Core.Expr` type.
+//!
+//! Expressions can be built from other values, which avoids having to generate code as a string
+//! that is evaluated with [`Value::eval_string`]. An expression can be evaluated with
+//! [`Module::eval`]:
+//!
+//! ```
+//! # use jlrs::prelude::*;
+//! # use jlrs::util::JULIA;
+//! # use jlrs::value::expr::Expr;
+//! # fn main() {
+//! # JULIA.with(|j| {
+//! # let mut julia = j.borrow_mut();
+//! julia.frame(4, |global, frame| {
+//!     let func = Module::base(global).function("+")?;
+//!     let a = Value::new(frame, 1u64)?;
+//!     let b = Value::new(frame, 2u64)?;
+//!     let expr = Expr::call(frame, func, &mut [a, b])?;
+//!
+//!     let result = Module::main(global).eval(frame, expr.as_value())?.unwrap();
+//!     assert_eq!(result.cast::<u64>()?, 3);
+//!     Ok(())
+//! }).unwrap();
+//! # });
+//! # }
+//! ```
+//!
+//! [`Value::eval_string`]: ../struct.Value.html#method.eval_string
+//! [`Module::eval`]: ../module/struct.Module.html#method.eval
 
 use super::array::Array;
+use super::module::Module;
 use super::symbol::Symbol;
-use super::Value;
-use crate::error::{JlrsError, JlrsResult};
-use crate::traits::Cast;
+use super::{CallResult, Value};
+use crate::error::{exception, JlrsError, JlrsResult};
+use crate::traits::{private::Internal, Cast, Frame, TemporarySymbol};
 use crate::{impl_julia_type, impl_julia_typecheck, impl_valid_layout};
-use jl_sys::{jl_expr_t, jl_expr_type};
+use jl_sys::{
+    jl_base_module, jl_core_module, jl_expr_t, jl_expr_type, jl_new_structv, jl_quotenode_type,
+};
 use std::marker::PhantomData;
 
 /// A compound expression in Julia ASTs.
@@ -24,6 +55,125 @@ impl<'frame> Expr<'frame> {
         self.0
     }
 
+    /// Create a new expression with the head `head` and the arguments `args`, this is
+    /// equivalent to `Expr(head, args...)` in Julia. This takes one slot on the GC stack,
+    /// returns an error if no slot is available.
+    pub fn new<'value, F, H, V>(frame: &mut F, head: H, args: &mut V) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+        H: TemporarySymbol,
+        V: AsMut<[Value<'value, 'static>]>,
+    {
+        unsafe {
+            let head: Value = head.temporary_symbol(Internal).into();
+            let args = args.as_mut();
+
+            let mut expr_args = Vec::with_capacity(args.len() + 1);
+            expr_args.push(head);
+            expr_args.extend_from_slice(args);
+
+            let constructor = Module::wrap(jl_core_module).global("Expr")?;
+            match constructor.call(frame, &mut expr_args)? {
+                Ok(expr) => Ok(expr.cast_unchecked::<Expr>()),
+                Err(exc) => exception(exc.type_name().into()),
+            }
+        }
+    }
+
+    /// Create a new `:call` expression that calls `func` with `args`, this is equivalent to
+    /// `:(func(args...))` in Julia. This takes one slot on the GC stack, returns an error if no
+    /// slot is available.
+    pub fn call<'value, F, V>(
+        frame: &mut F,
+        func: Value<'value, 'static>,
+        args: &mut V,
+    ) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+        V: AsMut<[Value<'value, 'static>]>,
+    {
+        let args = args.as_mut();
+        let mut call_args = Vec::with_capacity(args.len() + 1);
+        call_args.push(func);
+        call_args.extend_from_slice(args);
+
+        Expr::new(frame, "call", &mut call_args)
+    }
+
+    /// Create a new `:block` expression that contains the expressions in `args`, this is
+    /// equivalent to `begin; args...; end` in Julia. This takes one slot on the GC stack, returns
+    /// an error if no slot is available.
+    pub fn block<'value, F, V>(frame: &mut F, args: &mut V) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+        V: AsMut<[Value<'value, 'static>]>,
+    {
+        Expr::new(frame, "block", args)
+    }
+
+    /// Create a new `:function` expression with the signature `signature` and the body `body`,
+    /// the signature is usually a `:call` expression and the body a `:block` expression. This
+    /// takes one slot on the GC stack, returns an error if no slot is available.
+    pub fn function<'value, F>(
+        frame: &mut F,
+        signature: Value<'value, 'static>,
+        body: Value<'value, 'static>,
+    ) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+    {
+        Expr::new(frame, "function", &mut [signature, body])
+    }
+
+    /// Create a new `:(=)` expression that assigns `rhs` to `lhs`, this is equivalent to
+    /// `:(lhs = rhs)` in Julia. This takes one slot on the GC stack, returns an error if no slot
+    /// is available.
+    pub fn assign<'value, F>(
+        frame: &mut F,
+        lhs: Value<'value, 'static>,
+        rhs: Value<'value, 'static>,
+    ) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+    {
+        Expr::new(frame, "=", &mut [lhs, rhs])
+    }
+
+    /// Wrap `value` in a `QuoteNode`, which prevents it from being evaluated when the expression
+    /// that contains it is evaluated. This takes one slot on the GC stack, returns an error if
+    /// no slot is available.
+    pub fn quote_node<F>(
+        frame: &mut F,
+        value: Value<'_, 'static>,
+    ) -> JlrsResult<Value<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+    {
+        unsafe {
+            let mut fields = [value];
+            let node = jl_new_structv(jl_quotenode_type, fields.as_mut_ptr().cast(), 1);
+            frame.protect(node, Internal).map_err(Into::into)
+        }
+    }
+
+    /// Parse `code` with `Meta.parse`. The result is usually an `Expr`, but can also be a
+    /// `Symbol` or a literal value if `code` is a single identifier or literal. If `code` can't
+    /// be parsed the `ParseError` is returned as an exception. This takes two slots on the GC
+    /// stack, returns an error if they aren't available.
+    pub fn parse<F, S>(frame: &mut F, code: S) -> JlrsResult<CallResult<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+        S: AsRef<str>,
+    {
+        unsafe {
+            let parse = Module::wrap(jl_base_module)
+                .submodule("Meta")?
+                .function("parse")?;
+            let code = Value::new(frame, code.as_ref())?;
+            parse.call1(frame, code)
+        }
+    }
+
     /// Returns the head of the expression.
     pub fn head(self) -> Symbol<'frame> {
         unsafe { Symbol::wrap((&*self.ptr()).head) }
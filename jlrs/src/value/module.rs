@@ -1,17 +1,23 @@
 //! Access Julia modules and the globals and functions defined in them.
 
-use crate::error::{JlrsError, JlrsResult};
+use crate::error::{exception, JlrsError, JlrsResult};
 use crate::global::Global;
 use crate::traits::{private::Internal, Cast, Frame, TemporarySymbol};
+use crate::value::array::Array;
+use crate::value::expr::Expr;
 use crate::value::symbol::Symbol;
+use crate::value::value_ref::ValueRef;
 use crate::value::{CallResult, Value};
 use crate::{impl_julia_type, impl_julia_typecheck, impl_valid_layout};
 use jl_sys::{
-    jl_base_module, jl_core_module, jl_get_global, jl_main_module, jl_module_t, jl_module_type,
-    jl_set_const, jl_set_global, jl_typeis,
+    jl_base_module, jl_binding_t, jl_boundp, jl_checked_assignment, jl_core_module, jl_false,
+    jl_get_binding_wr, jl_get_global, jl_is_const, jl_main_module, jl_module_t, jl_module_type,
+    jl_set_const, jl_set_global, jl_true, jl_typeis,
 };
 use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::marker::PhantomData;
+use std::path::Path;
+use std::ptr::null_mut;
 
 /// Functionality in Julia can be accessed through its module system. You can get a handle to the
 /// three standard modules, `Main`, `Base`, and `Core` and access their submodules through them.
@@ -108,6 +114,234 @@ impl<'base> Module<'base> {
         }
     }
 
+    /// Create a new submodule named `name` in this module and return it. This is equivalent to
+    /// evaluating `module name end` in this module, so the new module uses `Base` and defines its
+    /// own `eval` and `include` functions. Returns an error if `name` is already defined in this
+    /// module, or an exception if the module can't be created. This takes three slots on the GC
+    /// stack, returns an error if they aren't available.
+    pub fn new_submodule<'frame, F, N>(
+        self,
+        frame: &mut F,
+        name: N,
+    ) -> JlrsResult<CallResult<'frame, 'static, Self>>
+    where
+        F: Frame<'frame>,
+        N: TemporarySymbol,
+    {
+        self.create_submodule(frame, name, true)
+    }
+
+    /// Create a new bare submodule named `name` in this module and return it. This is equivalent
+    /// to evaluating `baremodule name end` in this module, so nothing is imported into the new
+    /// module, not even `Base`. Returns an error if `name` is already defined in this module, or
+    /// an exception if the module can't be created. This takes three slots on the GC stack,
+    /// returns an error if they aren't available.
+    pub fn new_bare_submodule<'frame, F, N>(
+        self,
+        frame: &mut F,
+        name: N,
+    ) -> JlrsResult<CallResult<'frame, 'static, Self>>
+    where
+        F: Frame<'frame>,
+        N: TemporarySymbol,
+    {
+        self.create_submodule(frame, name, false)
+    }
+
+    /// Import the globals named `names` from the module `from` into this module, this is
+    /// equivalent to evaluating `import From: names...` in this module. Returns the exception
+    /// if one is thrown. This takes one slot on the GC stack, returns an error if no slot is
+    /// available.
+    pub fn import<'frame, F, N>(
+        self,
+        frame: &mut F,
+        from: Module,
+        names: &[N],
+    ) -> JlrsResult<CallResult<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+        N: TemporarySymbol,
+    {
+        unsafe {
+            let mut args = Vec::with_capacity(names.len() + 2);
+            args.push(self.as_value());
+            args.push(from.as_value());
+            for name in names {
+                args.push(name.temporary_symbol(Internal).into());
+            }
+
+            Module::wrap(jl_main_module)
+                .submodule("Jlrs")?
+                .function("importnames")?
+                .call(frame, &mut args)
+        }
+    }
+
+    /// Make all globals exported by the module `from` available in this module, this is
+    /// equivalent to evaluating `using From` in this module. Returns the exception if one is
+    /// thrown. This takes one slot on the GC stack, returns an error if no slot is available.
+    pub fn using<'frame, F>(
+        self,
+        frame: &mut F,
+        from: Module,
+    ) -> JlrsResult<CallResult<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+    {
+        unsafe {
+            Module::wrap(jl_main_module)
+                .submodule("Jlrs")?
+                .function("usingmodule")?
+                .call2(frame, self.as_value(), from.as_value())
+        }
+    }
+
+    /// Returns the names that are exported by this module, this is equivalent to calling
+    /// `names(module)`. This takes one slot on the GC stack, returns an error if no slot is
+    /// available.
+    pub fn exported_names<'frame, F>(self, frame: &mut F) -> JlrsResult<Vec<Symbol<'base>>>
+    where
+        F: Frame<'frame>,
+    {
+        self.names(frame, false, false)
+    }
+
+    /// Returns the names defined in this module, this is equivalent to calling
+    /// `names(module, all = all, imported = imported)`. If `all` is `false` only the exported
+    /// names are returned, otherwise non-exported and deprecated names are included too. If
+    /// `imported` is `true`, names that have been imported from other modules explicitly are
+    /// also included. This takes one slot on the GC stack, returns an error if no slot is
+    /// available.
+    pub fn names<'frame, F>(
+        self,
+        frame: &mut F,
+        all: bool,
+        imported: bool,
+    ) -> JlrsResult<Vec<Symbol<'base>>>
+    where
+        F: Frame<'frame>,
+    {
+        unsafe {
+            let names = Module::wrap(jl_main_module)
+                .submodule("Jlrs")?
+                .function("modulenames")?
+                .call3(
+                    frame,
+                    self.as_value(),
+                    Value::wrap(if all { jl_true } else { jl_false }),
+                    Value::wrap(if imported { jl_true } else { jl_false }),
+                )?;
+
+            let names = match names {
+                Ok(names) => names.cast::<Array>()?,
+                Err(exc) => return exception(exc.type_name().into()),
+            };
+
+            // Symbols are never freed by the garbage collector.
+            let names = names
+                .value_data(frame)?
+                .into_slice()
+                .iter()
+                .map(|name| Symbol::wrap(name.ptr().cast()))
+                .collect();
+
+            Ok(names)
+        }
+    }
+
+    /// Returns the modules that have been defined in this module. Modules that have been
+    /// imported from elsewhere are not included. This takes one slot on the GC stack, returns an
+    /// error if no slot is available.
+    pub fn submodules<'frame, F>(self, frame: &mut F) -> JlrsResult<Vec<Module<'base>>>
+    where
+        F: Frame<'frame>,
+    {
+        unsafe {
+            let modules = Module::wrap(jl_main_module)
+                .submodule("Jlrs")?
+                .function("submodules")?
+                .call1(frame, self.as_value())?;
+
+            let modules = match modules {
+                Ok(modules) => modules.cast::<Array>()?,
+                Err(exc) => return exception(exc.type_name().into()),
+            };
+
+            // Submodules are rooted by their parent.
+            let modules = modules
+                .value_data(frame)?
+                .into_slice()
+                .iter()
+                .map(|module| Module::wrap(module.ptr().cast()))
+                .collect();
+
+            Ok(modules)
+        }
+    }
+
+    /// Returns `true` if a global named `name` is defined in this module or has been made
+    /// available in this module with `using` or `import`.
+    pub fn is_defined<N>(self, name: N) -> bool
+    where
+        N: TemporarySymbol,
+    {
+        unsafe { jl_boundp(self.ptr(), name.temporary_symbol(Internal).ptr()) != 0 }
+    }
+
+    /// Returns `true` if a constant named `name` is defined in this module or has been made
+    /// available in this module with `using` or `import`.
+    pub fn is_const<N>(self, name: N) -> bool
+    where
+        N: TemporarySymbol,
+    {
+        unsafe { jl_is_const(self.ptr(), name.temporary_symbol(Internal).ptr()) != 0 }
+    }
+
+    /// Returns the [`Binding`] of the global named `name`, which contains its value, the module
+    /// that owns it, and whether it's constant and exported. Returns an error if the global
+    /// doesn't exist. The value is rooted in `frame`, which takes one slot on the GC stack. Four
+    /// more slots are used temporarily, an error is returned if they aren't available.
+    ///
+    /// [`Binding`]: struct.Binding.html
+    pub fn binding<'frame, F, N>(self, frame: &mut F, name: N) -> JlrsResult<Binding<'frame>>
+    where
+        F: Frame<'frame>,
+        N: TemporarySymbol,
+    {
+        unsafe {
+            let symbol = name.temporary_symbol(Internal);
+            if !self.is_defined(symbol) {
+                Err(JlrsError::FunctionNotFound(symbol.into()))?;
+            }
+
+            let is_const = self.is_const(symbol);
+            let func = Module::wrap(jl_main_module)
+                .submodule("Jlrs")?
+                .function("binding")?;
+
+            let output = frame.output()?;
+            frame.frame(4, |frame| {
+                let binding = match func.call2(frame, self.as_value(), symbol.into())? {
+                    Ok(binding) => binding,
+                    Err(exc) => return exception(exc.type_name().into()),
+                };
+
+                let owner = binding.get_nth_field(frame, 0)?.cast::<Module>()?;
+                let is_exported = binding.get_nth_field(frame, 1)?.cast::<bool>()?;
+                let value = binding.get_nth_field(frame, 2)?;
+
+                // The owner is rooted by its parent.
+                Ok(Binding {
+                    name: Symbol::wrap(symbol.ptr()),
+                    owner: Module::wrap(owner.ptr()),
+                    value: value.extend(frame, output),
+                    is_const,
+                    is_exported,
+                })
+            })
+        }
+    }
+
     /// Set a global value in this module. This is unsafe because if another global value was
     /// previously assigned to this name, this previous value can become eligible for garbage
     /// collection. Don't use the previous value after calling this method.
@@ -127,6 +361,73 @@ impl<'base> Module<'base> {
         Value::wrap(value.ptr())
     }
 
+    /// Set a global value in this module after checking that this is allowed. Unlike
+    /// [`Module::set_global`], this method returns an error if the global is a constant or if
+    /// it has been imported from another module, instead of throwing an exception. The value is
+    /// assigned with `jl_checked_assignment`, which applies the write barrier Julia uses when a
+    /// global is assigned. Julia 1.5 doesn't support declaring the type of a global, so any value
+    /// can be assigned to a global that isn't constant.
+    ///
+    /// If another value was previously assigned to this global, that value is rooted in `frame`
+    /// and returned so it remains valid after it has been replaced. This takes one slot on the GC
+    /// stack if the global was already defined, returns an error if it isn't available.
+    ///
+    /// [`Module::set_global`]: struct.Module.html#method.set_global
+    pub fn set_global_checked<'frame, F, N>(
+        self,
+        frame: &mut F,
+        name: N,
+        value: Value<'_, 'static>,
+    ) -> JlrsResult<Option<Value<'frame, 'static>>>
+    where
+        F: Frame<'frame>,
+        N: TemporarySymbol,
+    {
+        unsafe {
+            let symbol = name.temporary_symbol(Internal);
+            let binding = self.writable_binding(symbol)?;
+            let previous = self.root_previous(frame, binding)?;
+
+            jl_checked_assignment(binding, value.ptr());
+            Ok(previous)
+        }
+    }
+
+    /// Remove the value of the global named `name` from this module, after calling this method
+    /// the global is no longer defined. A new value can be assigned to it with
+    /// [`Module::set_global_checked`], which can be used to replace globals when code is
+    /// reloaded. Returns an error if the global doesn't exist, is a constant, or has been imported
+    /// from another module.
+    ///
+    /// The removed value is rooted in `frame` and returned so it remains valid after it has been
+    /// removed. This takes one slot on the GC stack, returns an error if it isn't available.
+    ///
+    /// [`Module::set_global_checked`]: struct.Module.html#method.set_global_checked
+    pub fn remove_global<'frame, F, N>(
+        self,
+        frame: &mut F,
+        name: N,
+    ) -> JlrsResult<Value<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+        N: TemporarySymbol,
+    {
+        unsafe {
+            let symbol = name.temporary_symbol(Internal);
+            if !self.is_defined(symbol) {
+                Err(JlrsError::FunctionNotFound(symbol.into()))?;
+            }
+
+            let binding = self.writable_binding(symbol)?;
+            let previous = self.root_previous(frame, binding)?.unwrap();
+
+            // Julia has no function that undefines a global. No write barrier is needed to
+            // clear the binding because it no longer references a value afterwards.
+            (&mut *binding).value = null_mut();
+            Ok(previous)
+        }
+    }
+
     /// Set a constant in this module.
     pub fn set_const<'frame, N>(
         self,
@@ -168,6 +469,22 @@ impl<'base> Module<'base> {
         }
     }
 
+    /// Returns a reference to the global named `name` in this module. Unlike [`Module::global`],
+    /// the reference must be rooted before it can be used as a `Value`, which protects it from
+    /// garbage collection if the global is changed later. See [`ValueRef`] for more information.
+    ///
+    /// Returns an error if the global doesn't exist.
+    ///
+    /// [`Module::global`]: struct.Module.html#method.global
+    /// [`ValueRef`]: ../value_ref/struct.ValueRef.html
+    pub fn global_ref<N>(self, name: N) -> JlrsResult<ValueRef<'base, 'static>>
+    where
+        N: TemporarySymbol,
+    {
+        self.global(name)
+            .map(|v| unsafe { ValueRef::wrap(v.ptr()) })
+    }
+
     /// Returns the function named `name` in this module. Note that all globals defined within the
     /// module will be successfully resolved into a function; Julia will throw an exception if you
     /// try to call something that isn't a function. This means that this method is just an alias
@@ -243,6 +560,214 @@ impl<'base> Module<'base> {
             Ok(out)
         }
     }
+
+    /// Evaluate `expr` in this module by calling `Core.eval`, which evaluates it as top-level
+    /// code with `jl_toplevel_eval_in`. The expression is usually an [`Expr`], but symbols and
+    /// other values can be evaluated too. Unlike calling `jl_toplevel_eval_in` directly, an
+    /// exception thrown during evaluation is caught and returned. This takes one slot on the GC
+    /// stack, returns an error if no slot is available.
+    ///
+    /// [`Expr`]: ../expr/struct.Expr.html
+    pub fn eval<'frame, F>(
+        self,
+        frame: &mut F,
+        expr: Value<'_, 'static>,
+    ) -> JlrsResult<CallResult<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+    {
+        unsafe {
+            Module::wrap(jl_core_module)
+                .function("eval")?
+                .call2(frame, self.as_value(), expr)
+        }
+    }
+
+    /// Evaluate `code` in this module, this is equivalent to calling
+    /// `Base.include_string(module, code)`. All top-level expressions in `code` are evaluated and
+    /// the result of the last one is returned. If an exception is thrown it's returned as a
+    /// `LoadError` that contains the line where it was thrown. This takes two slots on the GC
+    /// stack, returns an error if they aren't available.
+    pub fn eval_string<'frame, F, S>(
+        self,
+        frame: &mut F,
+        code: S,
+    ) -> JlrsResult<CallResult<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+        S: AsRef<str>,
+    {
+        unsafe {
+            let code = Value::new(frame, code.as_ref())?;
+            Module::wrap(jl_base_module)
+                .function("include_string")?
+                .call2(frame, self.as_value(), code)
+        }
+    }
+
+    /// Evaluate `code` in this module, this is equivalent to calling
+    /// `Base.include_string(module, code, filename)`. Unlike [`Module::eval_string`], error
+    /// messages and stacktraces refer to `filename`. This takes three slots on the GC stack,
+    /// returns an error if they aren't available.
+    ///
+    /// [`Module::eval_string`]: struct.Module.html#method.eval_string
+    pub fn include_string<'frame, F, S, N>(
+        self,
+        frame: &mut F,
+        code: S,
+        filename: N,
+    ) -> JlrsResult<CallResult<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+        S: AsRef<str>,
+        N: AsRef<str>,
+    {
+        unsafe {
+            let code = Value::new(frame, code.as_ref())?;
+            let filename = Value::new(frame, filename.as_ref())?;
+            Module::wrap(jl_base_module)
+                .function("include_string")?
+                .call3(frame, self.as_value(), code, filename)
+        }
+    }
+
+    /// Include the file at `path` in this module, this is equivalent to calling
+    /// `Base.include(module, path)`. Returns an error if the file doesn't exist. This takes two
+    /// slots on the GC stack, returns an error if they aren't available.
+    pub fn include<'frame, F, P>(
+        self,
+        frame: &mut F,
+        path: P,
+    ) -> JlrsResult<CallResult<'frame, 'static>>
+    where
+        F: Frame<'frame>,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        if !path.exists() {
+            Err(JlrsError::IncludeNotFound(path.to_string_lossy().into()))?;
+        }
+
+        unsafe {
+            let path = Value::new(frame, path.to_string_lossy())?;
+            Module::wrap(jl_base_module)
+                .function("include")?
+                .call2(frame, self.as_value(), path)
+        }
+    }
+
+    // Returns the binding of `symbol` in this module, which is created if it doesn't exist yet.
+    // Returns an error if the binding is owned by another module or is constant.
+    unsafe fn writable_binding(self, symbol: Symbol) -> JlrsResult<*mut jl_binding_t> {
+        // With `error` set to 0 this function doesn't throw if the binding is owned by another
+        // module, but returns that binding.
+        let binding = jl_get_binding_wr(self.ptr(), symbol.ptr(), 0);
+        let owner = (&*binding).owner;
+        if owner != self.ptr() {
+            let owner = Module::wrap(owner).name();
+            Err(JlrsError::ImportedGlobal(symbol.into(), owner.into()))?;
+        }
+
+        if self.is_const(symbol) {
+            Err(JlrsError::ConstGlobal(symbol.into()))?;
+        }
+
+        Ok(binding)
+    }
+
+    // Roots the value of `binding` in `frame` before it's replaced or removed, returns `None` if
+    // the binding has no value.
+    unsafe fn root_previous<'frame, F>(
+        self,
+        frame: &mut F,
+        binding: *mut jl_binding_t,
+    ) -> JlrsResult<Option<Value<'frame, 'static>>>
+    where
+        F: Frame<'frame>,
+    {
+        let value = (&*binding).value;
+        if value.is_null() {
+            return Ok(None);
+        }
+
+        frame.protect(value, Internal).map(Some).map_err(Into::into)
+    }
+
+    fn create_submodule<'frame, F, N>(
+        self,
+        frame: &mut F,
+        name: N,
+        std_imports: bool,
+    ) -> JlrsResult<CallResult<'frame, 'static, Self>>
+    where
+        F: Frame<'frame>,
+        N: TemporarySymbol,
+    {
+        unsafe {
+            let symbol = name.temporary_symbol(Internal);
+            if self.global(symbol).is_ok() {
+                Err(JlrsError::ConstAlreadyExists(symbol.into()))?;
+            }
+
+            let std_imports = Value::wrap(if std_imports { jl_true } else { jl_false });
+            let mut no_args: [Value; 0] = [];
+            let body = Expr::new(frame, "block", &mut no_args)?;
+            let expr = Expr::new(
+                frame,
+                "module",
+                &mut [std_imports, symbol.into(), body.into()],
+            )?;
+
+            let out = self
+                .eval(frame, expr.as_value())?
+                // transmute here to change the lifetime from 'frame to 'base, the new module is
+                // rooted by its parent.
+                .map(|m| std::mem::transmute(m.cast_unchecked::<Module>()));
+
+            Ok(out)
+        }
+    }
+}
+
+/// A global in a module, returned by [`Module::binding`].
+///
+/// [`Module::binding`]: struct.Module.html#method.binding
+#[derive(Copy, Clone, Debug)]
+pub struct Binding<'frame> {
+    name: Symbol<'frame>,
+    owner: Module<'frame>,
+    value: Value<'frame, 'static>,
+    is_const: bool,
+    is_exported: bool,
+}
+
+impl<'frame> Binding<'frame> {
+    /// The name of this global.
+    pub fn name(&self) -> Symbol<'frame> {
+        self.name
+    }
+
+    /// The module this global has been defined in. This is a different module than the one used
+    /// to look it up if the global has been imported.
+    pub fn owner(&self) -> Module<'frame> {
+        self.owner
+    }
+
+    /// The value of this global when the binding was looked up. The value is rooted in the frame
+    /// that was used to look it up, so it remains valid if a new value is assigned to the global.
+    pub fn value(&self) -> Value<'frame, 'static> {
+        self.value
+    }
+
+    /// Returns `true` if this global is a constant.
+    pub fn is_const(&self) -> bool {
+        self.is_const
+    }
+
+    /// Returns `true` if this global is exported by its owner.
+    pub fn is_exported(&self) -> bool {
+        self.is_exported
+    }
 }
 
 impl<'base> Into<Value<'base, 'static>> for Module<'base> {
@@ -1,8 +1,10 @@
 //! Support for values with the `Core.SimpleVector` (`SVec`) type.
 
 use crate::error::{JlrsError, JlrsResult};
+use crate::frame::Output;
 use crate::global::Global;
 use crate::traits::{private::Internal, Cast, Frame};
+use crate::value::value_ref::ValueRef;
 use crate::value::Value;
 use crate::{impl_julia_type, impl_julia_typecheck, impl_valid_layout};
 use jl_sys::{
@@ -41,6 +43,19 @@ impl<'frame> SimpleVector<'frame> {
         }
     }
 
+    /// Create a new `SimpleVector` that can hold `n` values and use the output to protect it from
+    /// garbage collection.
+    pub fn with_capacity_output<'fr, F>(frame: &mut F, output: Output<'frame>, n: usize) -> Self
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            let svec = jl_alloc_svec(n);
+            frame.assign_output(output, svec.cast(), Internal);
+            SimpleVector::wrap(svec)
+        }
+    }
+
     /// Create a new `SimpleVector` that can hold `n` values without initializing its contents.
     /// The contents must be set before calling Julia again.
     pub unsafe fn with_capacity_uninit<F>(frame: &mut F, n: usize) -> JlrsResult<Self>
@@ -55,6 +70,25 @@ impl<'frame> SimpleVector<'frame> {
         Ok(SimpleVector::wrap(svec))
     }
 
+    /// Create a new `SimpleVector` that can hold `n` values without initializing its contents
+    /// and use the output to protect it from garbage collection.
+    ///
+    /// # Safety
+    ///
+    /// The contents must be set before calling Julia again.
+    pub unsafe fn with_capacity_uninit_output<'fr, F>(
+        frame: &mut F,
+        output: Output<'frame>,
+        n: usize,
+    ) -> Self
+    where
+        F: Frame<'fr>,
+    {
+        let svec = jl_alloc_svec_uninit(n);
+        frame.assign_output(output, svec.cast(), Internal);
+        SimpleVector::wrap(svec)
+    }
+
     /// Returns the length of this `SimpleVector`.
     pub fn len(self) -> usize {
         unsafe { (&*self.ptr()).length }
@@ -65,6 +99,16 @@ impl<'frame> SimpleVector<'frame> {
         unsafe { std::slice::from_raw_parts(jl_svec_data(self.ptr()).cast(), self.len()) }
     }
 
+    /// Returns the data of this `SimpleVector` as [`ValueRef`]s. Unlike the `Value`s returned by
+    /// [`SimpleVector::data`], an undefined element is explicit and the elements must be rooted
+    /// before they can be used as a `Value` that outlives a change of this `SimpleVector`.
+    ///
+    /// [`ValueRef`]: ../value_ref/struct.ValueRef.html
+    /// [`SimpleVector::data`]: struct.SimpleVector.html#method.data
+    pub fn data_refs(self) -> &'frame [ValueRef<'frame, 'static>] {
+        unsafe { std::slice::from_raw_parts(jl_svec_data(self.ptr()).cast(), self.len()) }
+    }
+
     pub unsafe fn set<'data>(
         self,
         index: usize,
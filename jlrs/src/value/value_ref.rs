@@ -0,0 +1,203 @@
+//! References to values that are reachable from another value.
+//!
+//! Many values are reachable from a value that is already protected from garbage collection:
+//! the fields of a struct, the elements of an array or `SimpleVector`, and the globals of a
+//! module. As long as the parent is protected and the field, element or global isn't changed,
+//! the value it refers to isn't freed either. A [`ValueRef`] is such a reference. Creating,
+//! inspecting and traversing one never takes a slot on the GC stack, you only have to root it
+//! when you want to use it as a [`Value`].
+//!
+//! A `ValueRef` is not tied to a borrow of the frame, so nothing prevents you from calling Julia
+//! while you hold one. If Julia changes the parent in the meantime the referenced value can be
+//! freed, which is why rooting a `ValueRef` is unsafe:
+//!
+//! ```no_run
+//! # use jlrs::prelude::*;
+//! # fn main() {
+//! # let mut julia = unsafe { Julia::init(16).unwrap() };
+//! julia.frame(2, |_global, frame| {
+//!     let value = Value::eval_string(frame, "Ref(Ref([1, 2, 3]))")?.unwrap();
+//!
+//!     // No slots are used to reach `value.x.x`...
+//!     let data = value.get_field_ref("x")?.get_field_ref("x")?;
+//!     assert!(data.is::<Array>());
+//!
+//!     // ...until it's rooted. Julia hasn't been called since `value` was created, so
+//!     // `value.x.x` can't have been freed.
+//!     let data = unsafe { data.root(frame)? }.cast::<Array>()?;
+//!     assert_eq!(frame.size(), 2);
+//!     Ok(())
+//! }).unwrap();
+//! # }
+//! ```
+//!
+//! [`ValueRef`]: struct.ValueRef.html
+//! [`Value`]: ../struct.Value.html
+
+use super::datatype::DataType;
+use super::Value;
+use crate::error::{JlrsError, JlrsResult};
+use crate::frame::Output;
+use crate::traits::{private::Internal, Frame, JuliaTypecheck, TemporarySymbol};
+use jl_sys::{jl_typeof, jl_typeof_str, jl_value_t};
+use std::ffi::CStr;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::marker::PhantomData;
+
+/// A reference to a value that is reachable from a parent that is protected from garbage
+/// collection, like a field of a struct or an element of a value array. The lifetime `'parent`
+/// is the lifetime of that parent, a `ValueRef` doesn't take a slot on the GC stack.
+///
+/// A `ValueRef` remains valid as long as the field or element it has been read from isn't
+/// changed. Changing it from Rust requires calling an unsafe method like
+/// [`Value::set_nth_field`], but Julia code can change it too. Because a `ValueRef` can be
+/// copied and used after calling Julia, rooting it with [`ValueRef::root`] is unsafe. If the
+/// reference must stay valid after calling Julia, root it before doing so.
+///
+/// A `ValueRef` can be undefined if it refers to an uninitialized field or element.
+///
+/// [`Value::set_nth_field`]: ../struct.Value.html#method.set_nth_field
+/// [`ValueRef::root`]: struct.ValueRef.html#method.root
+#[derive(Copy, Clone, Hash, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct ValueRef<'parent, 'data>(
+    *mut jl_value_t,
+    PhantomData<&'parent ()>,
+    PhantomData<&'data ()>,
+);
+
+impl<'parent, 'data> ValueRef<'parent, 'data> {
+    pub(crate) unsafe fn wrap(ptr: *mut jl_value_t) -> Self {
+        ValueRef(ptr, PhantomData, PhantomData)
+    }
+
+    #[doc(hidden)]
+    pub unsafe fn ptr(self) -> *mut jl_value_t {
+        self.0
+    }
+
+    /// Returns `true` if this reference is undefined.
+    pub fn is_undefined(self) -> bool {
+        self.0.is_null()
+    }
+
+    /// Returns the `DataType` of the referenced value, or `None` if the reference is undefined.
+    pub fn datatype(self) -> Option<DataType<'parent>> {
+        unsafe {
+            if self.is_undefined() {
+                return None;
+            }
+
+            Some(DataType::wrap(jl_typeof(self.ptr()).cast()))
+        }
+    }
+
+    /// Returns the type name of the referenced value.
+    pub fn type_name(self) -> &'parent str {
+        unsafe {
+            if self.is_undefined() {
+                return "null";
+            }
+
+            CStr::from_ptr(jl_typeof_str(self.ptr())).to_str().unwrap()
+        }
+    }
+
+    /// Performs the same check as [`Value::is`] on the referenced value. Returns `false` if the
+    /// reference is undefined.
+    ///
+    /// [`Value::is`]: ../struct.Value.html#method.is
+    pub fn is<T: JuliaTypecheck>(self) -> bool {
+        match self.datatype() {
+            Some(dt) => dt.is::<T>(),
+            None => false,
+        }
+    }
+
+    /// Returns a reference to the field at index `idx` of the referenced value, see
+    /// [`Value::get_nth_field_ref`] for more information. Returns `JlrsError::UndefRef` if this
+    /// reference is undefined.
+    ///
+    /// [`Value::get_nth_field_ref`]: ../struct.Value.html#method.get_nth_field_ref
+    pub fn get_nth_field_ref(self, idx: usize) -> JlrsResult<ValueRef<'parent, 'data>> {
+        self.value()?.get_nth_field_ref(idx)
+    }
+
+    /// Returns a reference to the field named `field_name` of the referenced value, see
+    /// [`Value::get_field_ref`] for more information. Returns `JlrsError::UndefRef` if this
+    /// reference is undefined.
+    ///
+    /// [`Value::get_field_ref`]: ../struct.Value.html#method.get_field_ref
+    pub fn get_field_ref<N>(self, field_name: N) -> JlrsResult<ValueRef<'parent, 'data>>
+    where
+        N: TemporarySymbol,
+    {
+        self.value()?.get_field_ref(field_name)
+    }
+
+    /// Protect the referenced value from garbage collection, this takes one slot on the GC
+    /// stack. Returns `JlrsError::UndefRef` if this reference is undefined.
+    ///
+    /// # Safety
+    ///
+    /// The field or element this reference has been read from must not have been changed since
+    /// the reference was created, otherwise the referenced value might already have been freed.
+    /// Calling Julia can change it.
+    #[cfg_attr(feature = "debug-stack", track_caller)]
+    pub unsafe fn root<'fr, F>(self, frame: &mut F) -> JlrsResult<Value<'fr, 'data>>
+    where
+        F: Frame<'fr>,
+    {
+        let value = self.value()?;
+        frame
+            .protect(value.ptr(), Internal)
+            .map(|v| Value(v.ptr(), PhantomData, PhantomData))
+            .map_err(Into::into)
+    }
+
+    /// Protect the referenced value from garbage collection by assigning it to `output`. Returns
+    /// `JlrsError::UndefRef` if this reference is undefined.
+    ///
+    /// # Safety
+    ///
+    /// The same rules apply as for [`ValueRef::root`].
+    ///
+    /// [`ValueRef::root`]: struct.ValueRef.html#method.root
+    pub unsafe fn root_output<'output, 'fr, F>(
+        self,
+        frame: &mut F,
+        output: Output<'output>,
+    ) -> JlrsResult<Value<'output, 'data>>
+    where
+        F: Frame<'fr>,
+    {
+        let value = self.value()?;
+        let v = frame.assign_output(output, value.ptr(), Internal);
+        Ok(Value(v.ptr(), PhantomData, PhantomData))
+    }
+
+    /// Use the referenced value without rooting it. The `Value` is null if the reference is
+    /// undefined.
+    ///
+    /// # Safety
+    ///
+    /// The value is only valid as long as the field or element it has been read from isn't
+    /// changed.
+    pub unsafe fn value_unchecked(self) -> Value<'parent, 'data> {
+        Value(self.ptr(), PhantomData, PhantomData)
+    }
+
+    fn value(self) -> JlrsResult<Value<'parent, 'data>> {
+        if self.is_undefined() {
+            Err(JlrsError::UndefRef)?;
+        }
+
+        unsafe { Ok(self.value_unchecked()) }
+    }
+}
+
+impl<'parent, 'data> Debug for ValueRef<'parent, 'data> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_tuple("ValueRef").field(&self.type_name()).finish()
+    }
+}
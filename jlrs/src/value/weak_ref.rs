@@ -2,12 +2,13 @@
 
 use super::Value;
 use crate::error::{JlrsError, JlrsResult};
-use crate::traits::Cast;
+use crate::traits::{private::Internal, Cast, Frame};
 use crate::{impl_julia_type, impl_julia_typecheck, impl_valid_layout};
-use jl_sys::{jl_weakref_t, jl_weakref_type};
+use jl_sys::{jl_gc_new_weakref, jl_nothing, jl_weakref_t, jl_weakref_type};
 use std::marker::PhantomData;
 
-/// A weak reference.
+/// A weak reference. A weak reference doesn't prevent the referenced value from being freed by
+/// the garbage collector, after it has been freed the weak reference refers to `nothing`.
 #[derive(Copy, Clone, Hash, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct WeakRef<'frame>(*mut jl_weakref_t, PhantomData<&'frame ()>);
@@ -22,7 +23,41 @@ impl<'frame> WeakRef<'frame> {
         self.0
     }
 
-    /// The referenced `Value`.
+    /// Create a new weak reference to `value`, this takes one slot on the GC stack.
+    pub fn new<F>(frame: &mut F, value: Value<'_, 'static>) -> JlrsResult<Self>
+    where
+        F: Frame<'frame>,
+    {
+        unsafe {
+            let weak_ref = jl_gc_new_weakref(value.ptr());
+            frame
+                .protect(weak_ref.cast(), Internal)
+                .map_err(Into::into)
+                .map(|v| Self::wrap(v.ptr().cast()))
+        }
+    }
+
+    /// Returns the referenced value if it hasn't been freed by the garbage collector yet. The
+    /// value is protected from garbage collection in `frame`, this takes one slot on the GC
+    /// stack if the value still exists.
+    pub fn upgrade<'fr, F>(self, frame: &mut F) -> JlrsResult<Option<Value<'fr, 'static>>>
+    where
+        F: Frame<'fr>,
+    {
+        unsafe {
+            let value = (&*self.ptr()).value;
+            if value == jl_nothing {
+                return Ok(None);
+            }
+
+            frame.protect(value, Internal).map(Some).map_err(Into::into)
+        }
+    }
+
+    /// The referenced `Value`. This value is not protected from garbage collection, use
+    /// [`WeakRef::upgrade`] to protect it.
+    ///
+    /// [`WeakRef::upgrade`]: struct.WeakRef.html#method.upgrade
     pub fn value(self) -> Value<'frame, 'static> {
         unsafe { Value::wrap((&*self.ptr()).value) }
     }
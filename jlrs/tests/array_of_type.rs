@@ -0,0 +1,150 @@
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+
+#[test]
+fn array_of_any() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |global, frame| {
+            let elty = DataType::any_type(global).as_value();
+            let arr = Value::new_array_of_type(frame, elty, (2, 3))?.cast::<Array>()?;
+            assert!(arr.is_value_array());
+            assert_eq!(arr.dimensions().as_slice(), &[2, 3]);
+            assert!(arr.element_type().egal(elty));
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn array_of_union() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(3, |global, frame| {
+            let mut types = [
+                DataType::int64_type(global).as_value(),
+                DataType::nothing_type(global).as_value(),
+            ];
+            let elty = Value::new_union(frame, &mut types)?;
+            let arr = Value::new_array_of_type(frame, elty, 4)?.cast::<Array>()?;
+            assert_eq!(arr.dimensions().as_slice(), &[4]);
+            assert!(arr.element_type().egal(elty));
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn array_of_type_output() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(1, |global, frame| {
+            let output = frame.output()?;
+            let elty = DataType::float32_type(global).as_value();
+            let arr = Value::new_array_of_type_output(frame, output, elty, (1, 2, 3, 4))?;
+            assert!(arr.is_array_of::<f32>());
+            assert_eq!(arr.cast::<Array>()?.dimensions().as_slice(), &[1, 2, 3, 4]);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn array_of_type_not_a_type() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |_, frame| {
+            let elty = Value::new(frame, 1usize)?;
+            assert!(Value::new_array_of_type(frame, elty, 4).is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn filled_inline_array() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let filled = jlrs
+            .frame(2, |_, frame| {
+                let value = Value::new(frame, 3.0f64)?;
+                let arr = Value::filled_array(frame, value, (2, 2))?;
+                arr.cast::<Array>()?.copy_inline_data::<f64>()
+            })
+            .unwrap();
+
+        let (data, dims) = filled.splat();
+        assert_eq!(dims.as_slice(), &[2, 2]);
+        assert_eq!(data, vec![3.0; 4]);
+    });
+}
+
+#[test]
+fn filled_value_array() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(3, |global, frame| {
+            let value = Value::new(frame, "foo")?;
+            let output = frame.output()?;
+            let arr = Value::filled_array_output(frame, output, value, 3)?.cast::<Array>()?;
+            let data = unsafe { arr.value_data(frame)? };
+
+            for i in 0..3 {
+                assert!(data[i].egal(value));
+            }
+
+            assert!(arr
+                .element_type()
+                .egal(DataType::string_type(global).as_value()));
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn zeros_array() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let zeros = jlrs
+            .frame(1, |_, frame| {
+                let arr = Value::zeros_array::<u32, _, _>(frame, (3, 2))?;
+                arr.cast::<Array>()?.copy_inline_data::<u32>()
+            })
+            .unwrap();
+
+        let (data, dims) = zeros.splat();
+        assert_eq!(dims.as_slice(), &[3, 2]);
+        assert_eq!(data, vec![0; 6]);
+    });
+}
+
+#[test]
+fn zeros_array_of_type() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let zeros = jlrs
+            .frame(1, |global, frame| {
+                let output = frame.output()?;
+                let elty = DataType::int16_type(global).as_value();
+                let arr = Value::zeros_array_of_type_output(frame, output, elty, 5)?;
+                arr.cast::<Array>()?.copy_inline_data::<i16>()
+            })
+            .unwrap();
+
+        let (data, _) = zeros.splat();
+        assert_eq!(data, vec![0; 5]);
+    });
+}
@@ -0,0 +1,80 @@
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+
+#[test]
+fn indexed_iter_inline_data() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let mut data: Vec<u32> = (0..6).collect();
+
+        jlrs.frame(1, |_, frame| {
+            let arr = Value::borrow_array(frame, &mut data, (2, 3))?.cast::<Array>()?;
+            let data = arr.inline_data::<u32, _>(frame)?;
+            let view = data.as_view();
+
+            for (index, value) in view.indexed_iter() {
+                assert_eq!(data.dimensions().index_of(index)?, *value as usize);
+            }
+
+            assert_eq!(view.iter().sum::<u32>(), 15);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn iter_mut_inline_data() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let data = jlrs
+            .frame(1, |_, frame| {
+                let arr = Value::new_array::<u32, _, _>(frame, [2, 2])?.cast::<Array>()?;
+
+                {
+                    let mut data = arr.inline_data_mut::<u32, _>(frame)?;
+                    for (index, value) in data.as_view_mut().indexed_iter_mut() {
+                        let index = index.as_slice();
+                        *value = (10 * index[0] + index[1]) as u32;
+                    }
+                }
+
+                arr.copy_inline_data::<u32>()
+            })
+            .unwrap();
+
+        assert_eq!(data.as_slice(), &[0, 10, 1, 11]);
+    });
+}
+
+#[test]
+fn view_inline_data() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let mut data: Vec<f64> = (0..12).map(|x| x as f64).collect();
+
+        jlrs.frame(1, |_, frame| {
+            let arr = Value::borrow_array(frame, &mut data, vec![3, 4])?.cast::<Array>()?;
+            let data = arr.inline_data::<f64, _>(frame)?;
+            let data = data.as_view();
+
+            let view = data.view([1..3, 2..4])?;
+            assert_eq!(view.dimensions().as_slice(), &[2, 2]);
+            assert_eq!(view[(0, 0)], 7.0);
+            assert_eq!(
+                view.iter().copied().collect::<Vec<_>>(),
+                [7.0, 8.0, 10.0, 11.0]
+            );
+            assert!(data.view([0..4, 0..1]).is_err());
+
+            let sums: Vec<f64> = data
+                .axis_iter(1)?
+                .map(|column| column.iter().sum())
+                .collect();
+            assert_eq!(sums, [3.0, 12.0, 21.0, 30.0]);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "arrow")]
+
+use arrow::array::{Array as ArrowArray, AsArray, Float64Array, Int64Array};
+use arrow::datatypes::{DataType as ArrowDataType, Field, Float64Type, Int64Type, Schema};
+use arrow::record_batch::RecordBatch;
+use jlrs::arrow::{borrow_record_batch, to_record_batch};
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+use std::sync::Arc;
+
+fn record_batch() -> RecordBatch {
+    let schema = Schema::new(vec![
+        Field::new("a", ArrowDataType::Int64, false),
+        Field::new("b", ArrowDataType::Float64, true),
+    ]);
+
+    let a = Int64Array::from(vec![1, 2, 3]);
+    let b = Float64Array::from(vec![Some(1.0), None, Some(3.0)]);
+
+    RecordBatch::try_new(Arc::new(schema), vec![Arc::new(a), Arc::new(b)]).unwrap()
+}
+
+#[test]
+fn borrow_record_batch_columns() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let batch = record_batch();
+
+        let (sum, n_missing) = jlrs
+            .dynamic_frame(|global, frame| {
+                let table = borrow_record_batch(frame, &batch)?;
+                let a = table.get_field(frame, "a")?;
+                let b = table.get_field(frame, "b")?;
+
+                let sum = Module::base(global)
+                    .function("sum")?
+                    .call1(frame, a)?
+                    .unwrap()
+                    .cast::<i64>()?;
+
+                let n_missing = Module::base(global)
+                    .function("count")?
+                    .call2(frame, Module::base(global).function("ismissing")?, b)?
+                    .unwrap()
+                    .cast::<i64>()?;
+
+                Ok((sum, n_missing))
+            })
+            .unwrap();
+
+        assert_eq!(sum, 6);
+        assert_eq!(n_missing, 1);
+    });
+}
+
+#[test]
+fn record_batch_roundtrip() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let batch = record_batch();
+
+        let copied = jlrs
+            .dynamic_frame(|_global, frame| {
+                let table = borrow_record_batch(frame, &batch)?;
+                to_record_batch(frame, table)
+            })
+            .unwrap();
+
+        assert_eq!(copied.num_columns(), 2);
+        assert_eq!(copied.schema().field(0).name(), "a");
+        assert!(!copied.schema().field(0).is_nullable());
+        assert!(copied.schema().field(1).is_nullable());
+
+        let a = copied.column(0).as_primitive::<Int64Type>();
+        assert_eq!(a.values().as_ref(), &[1, 2, 3]);
+
+        let b = copied.column(1).as_primitive::<Float64Type>();
+        assert!(b.is_valid(0));
+        assert!(b.is_null(1));
+        assert_eq!(b.value(2), 3.0);
+    });
+}
+
+#[test]
+fn borrowed_columns_are_read_only() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let batch = record_batch();
+
+        jlrs.dynamic_frame(|global, frame| {
+            let table = borrow_record_batch(frame, &batch)?;
+            let setindex = Module::base(global).function("setindex!")?;
+            let value = Value::new(frame, 4i64)?;
+            let idx = Value::new(frame, 1usize)?;
+
+            for column in &["a", "b"] {
+                let column = table.get_field(frame, *column)?;
+                assert!(setindex.call3(frame, column, value, idx)?.is_err());
+            }
+
+            Ok(())
+        })
+        .unwrap();
+
+        let a = batch.column(0).as_primitive::<Int64Type>();
+        assert_eq!(a.values().as_ref(), &[1, 2, 3]);
+    });
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "debug-stack")]
+
+use jlrs::debug_stack::{self, FrameKind};
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+
+#[test]
+fn report_frame_overflow() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let res = jlrs.frame(1, |_global, frame| {
+            Value::new(frame, 1usize)?;
+            Value::new(frame, 2usize)?;
+            Ok(())
+        });
+
+        assert!(res.is_err());
+
+        let report = debug_stack::alloc_error_report().unwrap();
+        assert_eq!(report.frames.len(), 1);
+
+        let frame = &report.frames[0];
+        assert_eq!(frame.kind, FrameKind::Static(1));
+        assert_eq!(frame.used(), 1);
+        assert!(frame.created_at.file().ends_with("debug_stack.rs"));
+        assert!(frame.slots[0].unwrap().file().ends_with("debug_stack.rs"));
+        assert!(report.to_string().contains("StaticFrame"));
+
+        assert!(debug_stack::alloc_error_report().is_none());
+    });
+}
+
+#[test]
+fn record_peak_usage() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.dynamic_frame(|_global, frame| {
+            frame.frame(4, |frame| {
+                Value::new(frame, 1usize)?;
+                let report = debug_stack::report().unwrap();
+                assert_eq!(report.frames.len(), 2);
+                assert_eq!(report.frames[1].used(), 1);
+                Ok(())
+            })?;
+
+            Value::new(frame, 2usize)?;
+            Ok(())
+        })
+        .unwrap();
+
+        // The dynamic frame and the nested static frame each take two slots, the static frame
+        // has four additional slots.
+        let peak = debug_stack::last_peak().unwrap();
+        assert!(peak >= 8);
+        assert!(debug_stack::max_peak() >= peak);
+
+        // The stack is no longer recorded after its outermost frame has been dropped.
+        assert!(debug_stack::report().is_none());
+    });
+}
+
+#[test]
+fn report_of_frame() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |_global, frame| {
+            Value::new(frame, 1usize)?;
+            let report = debug_stack::frame_report(frame).unwrap();
+            assert_eq!(report.frames.len(), 1);
+            assert_eq!(report.frames[0].used(), 1);
+            assert!(report.peak >= 3);
+
+            frame.frame(0, |frame| {
+                let report = debug_stack::frame_report(frame).unwrap();
+                assert_eq!(report.frames.len(), 2);
+                Ok(())
+            })
+        })
+        .unwrap();
+    });
+}
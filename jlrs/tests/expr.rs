@@ -0,0 +1,115 @@
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+use jlrs::value::expr::Expr;
+use jlrs::value::symbol::Symbol;
+
+#[test]
+fn eval_call_expr() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let out = jlrs
+            .frame(4, |global, frame| {
+                let func = Module::base(global).function("+")?;
+                let a = Value::new(frame, 1u64)?;
+                let b = Value::new(frame, 2u64)?;
+                let expr = Expr::call(frame, func, &mut [a, b])?;
+                assert_eq!(expr.head().as_string(), "call");
+
+                Module::main(global)
+                    .eval(frame, expr.as_value())?
+                    .unwrap()
+                    .cast::<u64>()
+            })
+            .unwrap();
+
+        assert_eq!(out, 3);
+    });
+}
+
+#[test]
+fn eval_function_definition() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let out = jlrs
+            .frame(10, |global, frame| {
+                // function exprdouble(x) x * 2 end
+                let name: Value = Symbol::new(global, "exprdouble").into();
+                let x: Value = Symbol::new(global, "x").into();
+                let times = Module::base(global).function("*")?;
+                let two = Value::new(frame, 2i64)?;
+
+                let signature = Expr::call(frame, name, &mut [x])?.as_value();
+                let product = Expr::call(frame, times, &mut [x, two])?.as_value();
+                let body = Expr::block(frame, &mut [product])?.as_value();
+                let function = Expr::function(frame, signature, body)?;
+                Module::main(global)
+                    .eval(frame, function.as_value())?
+                    .unwrap();
+
+                let func = Module::main(global).function("exprdouble")?;
+                let arg = Value::new(frame, 4i64)?;
+                func.call1(frame, arg)?.unwrap().cast::<i64>()
+            })
+            .unwrap();
+
+        assert_eq!(out, 8);
+    });
+}
+
+#[test]
+fn eval_assignment() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(4, |global, frame| {
+            let name: Value = Symbol::new(global, "expr_assigned").into();
+            let value = Value::new(frame, "foo")?;
+            let quoted = Expr::quote_node(frame, value)?;
+            let assignment = Expr::assign(frame, name, quoted)?;
+            Module::main(global)
+                .eval(frame, assignment.as_value())?
+                .unwrap();
+
+            let assigned = Module::main(global).global("expr_assigned")?;
+            assert_eq!(assigned.cast::<String>()?, "foo");
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn parse_and_eval() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(6, |global, frame| {
+            let parsed = Expr::parse(frame, "3 + 4")?.unwrap();
+            let expr = parsed.cast::<Expr>()?;
+            assert_eq!(expr.head().as_string(), "call");
+
+            let out = Module::main(global).eval(frame, parsed)?.unwrap();
+            assert_eq!(out.cast::<i64>()?, 7);
+
+            assert!(Expr::parse(frame, "x = )")?.is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn eval_exception() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(3, |global, frame| {
+            let expr = Expr::parse(frame, "error(\"oops\")")?.unwrap();
+            assert!(Module::main(global).eval(frame, expr)?.is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
@@ -0,0 +1,91 @@
+use jlrs::persistent::PersistentValue;
+use jlrs::prelude::*;
+use jlrs::traits::gc::{Gc, GcCollection};
+use jlrs::util::JULIA;
+use jlrs::value::weak_ref::WeakRef;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn rust_finalizer_is_called() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let finalized = Arc::new(AtomicBool::new(false));
+        let flag = finalized.clone();
+
+        jlrs.frame(2, |_global, frame| {
+            let value = Value::eval_string(frame, "Ref(1)")?.unwrap();
+            value.add_rust_finalizer(frame, move || flag.store(true, Ordering::SeqCst))?;
+            Ok(())
+        })
+        .unwrap();
+
+        unsafe {
+            jlrs.gc_collect(GcCollection::Full);
+            jlrs.gc_collect(GcCollection::Full);
+        }
+
+        assert!(finalized.load(Ordering::SeqCst));
+    });
+}
+
+#[test]
+fn rust_finalizer_immutable() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(1, |_global, frame| {
+            let value = Value::new(frame, 1usize)?;
+            assert!(value.add_rust_finalizer(frame, || ()).is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn weak_ref_upgrade() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(3, |_global, frame| {
+            let value = Value::eval_string(frame, "Ref(1)")?.unwrap();
+            let weak_ref = WeakRef::new(frame, value)?;
+            let upgraded = weak_ref.upgrade(frame)?.unwrap();
+            assert!(upgraded.egal(value));
+            Ok(())
+        })
+        .unwrap();
+
+        // Only the weak reference remains rooted after this frame has been dropped. The finalizer
+        // is called after the value has become unreachable, at which point the weak reference
+        // has been cleared.
+        let finalized = Arc::new(AtomicBool::new(false));
+        let flag = finalized.clone();
+        let weak_ref = jlrs
+            .frame(2, |_global, frame| {
+                let value = Value::eval_string(frame, "Ref(2)")?.unwrap();
+                value.add_rust_finalizer(frame, move || flag.store(true, Ordering::SeqCst))?;
+                let weak_ref = WeakRef::new(frame, value)?;
+                PersistentValue::new(frame, weak_ref.as_value())
+            })
+            .unwrap();
+
+        for _ in 0..10 {
+            unsafe {
+                jlrs.gc_collect(GcCollection::Full);
+            }
+
+            if finalized.load(Ordering::SeqCst) {
+                break;
+            }
+        }
+
+        assert!(finalized.load(Ordering::SeqCst));
+        jlrs.frame(1, |_global, frame| {
+            assert!(weak_ref.cast::<WeakRef>()?.upgrade(frame)?.is_none());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
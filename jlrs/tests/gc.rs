@@ -0,0 +1,67 @@
+use jlrs::prelude::*;
+use jlrs::traits::gc::{Gc, GcCollection};
+use jlrs::util::JULIA;
+
+#[test]
+fn gc_stats() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let before = jlrs.gc_stats().unwrap();
+        jlrs.frame(1, |_global, frame| {
+            Value::eval_string(frame, "zeros(1024)")?.unwrap();
+            Ok(())
+        })
+        .unwrap();
+
+        unsafe {
+            jlrs.gc_collect(GcCollection::Full);
+        }
+
+        let after = jlrs.gc_stats().unwrap();
+        let diff = after.diff(&before);
+
+        assert!(diff.allocd >= 8 * 1024);
+        assert!(diff.pause >= 1);
+        assert!(diff.full_sweep >= 1);
+        assert!(jlrs.gc_live_bytes().unwrap() > 0);
+    });
+}
+
+#[test]
+fn gc_disabled_guard() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        assert!(jlrs.gc_is_enabled());
+
+        {
+            let _guard = unsafe { jlrs.gc_disabled() };
+            assert!(!jlrs.gc_is_enabled());
+            jlrs.gc_safepoint();
+        }
+
+        assert!(jlrs.gc_is_enabled());
+
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = unsafe { jlrs.gc_disabled() };
+            panic!("oops");
+        }));
+
+        assert!(res.is_err());
+        assert!(jlrs.gc_is_enabled());
+    });
+}
+
+#[test]
+fn gc_tuning_unsupported() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        match *jlrs.gc_set_interval(1 << 20).unwrap_err() {
+            JlrsError::Unsupported(_, version) => assert_eq!(version, jlrs::JULIA_VERSION),
+            _ => panic!("Expected JlrsError::Unsupported"),
+        }
+
+        assert!(jlrs.gc_set_heap_size_hint(1 << 30).is_err());
+    });
+}
@@ -0,0 +1,17 @@
+use jlrs::util::JULIA;
+
+#[test]
+fn julia_info() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let info = jlrs.info().unwrap();
+
+        assert_eq!(info.version, jlrs::version().unwrap());
+        assert!(info.version_string.starts_with(jlrs::JULIA_VERSION));
+        assert!(info.cpu_threads >= 1);
+        assert!(info.n_threads >= 1);
+        assert!(!info.sysimage_path.as_os_str().is_empty());
+        assert!(info.bindir.exists());
+        assert_eq!(info.word_size, 8 * std::mem::size_of::<usize>());
+    });
+}
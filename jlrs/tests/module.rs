@@ -1,4 +1,5 @@
 use jlrs::prelude::*;
+use jlrs::traits::gc::{Gc, GcCollection};
 use jlrs::util::JULIA;
 use std::borrow::Cow;
 
@@ -280,3 +281,278 @@ fn set_const_twice() {
         assert!(err.is_err());
     })
 }
+
+#[test]
+fn eval_string_in_module() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(6, |global, frame| {
+            let main = Module::main(global);
+            main.eval_string(frame, "module EvalStringTest end")?
+                .unwrap();
+
+            let module = main.submodule("EvalStringTest")?;
+            let value = module.eval_string(frame, "const X = 3\nX + 1")?.unwrap();
+            assert_eq!(value.cast::<i64>()?, 4);
+            assert!(module.global("X").is_ok());
+            assert!(main.global("X").is_err());
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn include_string_in_module() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(8, |global, frame| {
+            let main = Module::main(global);
+            main.eval_string(frame, "module IncludeStringTest end")?
+                .unwrap();
+
+            let module = main.submodule("IncludeStringTest")?;
+            module
+                .include_string(frame, "f(x) = 2x", "plugin.jl")?
+                .unwrap();
+
+            let arg = Value::new(frame, 2i64)?;
+            let res = module.function("f")?.call1(frame, arg)?.unwrap();
+            assert_eq!(res.cast::<i64>()?, 4);
+
+            let err = module
+                .include_string(frame, "\nerror(\"oops\")", "plugin.jl")?
+                .unwrap_err();
+            assert_eq!(err.type_name(), "LoadError");
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn include_in_module() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(4, |global, frame| {
+            let main = Module::main(global);
+            main.eval_string(frame, "module IncludeTest end")?.unwrap();
+
+            let module = main.submodule("IncludeTest")?;
+            assert!(module.include(frame, "nonexistent.jl").is_err());
+            module.include(frame, "tests/julia/JlrsTests.jl")?.unwrap();
+            assert!(module.submodule("JlrsTests").is_ok());
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn new_submodule() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(8, |global, frame| {
+            let main = Module::main(global);
+            let module = main.new_submodule(frame, "NewSubmodule")?.unwrap();
+            assert_eq!(module.name().as_string(), "NewSubmodule");
+            assert!(main.submodule("NewSubmodule").is_ok());
+            assert!(main.new_submodule(frame, "NewSubmodule").is_err());
+
+            // Base is available in a normal module.
+            let res = module.eval_string(frame, "sqrt(4.0)")?.unwrap();
+            assert_eq!(res.cast::<f64>()?, 2.0);
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn new_bare_submodule() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(6, |global, frame| {
+            let main = Module::main(global);
+            let module = main.new_bare_submodule(frame, "NewBareSubmodule")?.unwrap();
+            assert!(module.global("sqrt").is_err());
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn import_and_using() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(16, |global, frame| {
+            let main = Module::main(global);
+            let source = main.new_submodule(frame, "ImportSource")?.unwrap();
+            source
+                .eval_string(frame, "export exported\nexported() = 1\nhidden() = 2")?
+                .unwrap();
+
+            let names = source.exported_names(frame)?;
+            let names: Vec<String> = names.into_iter().map(|n| n.as_string()).collect();
+            assert!(names.contains(&"exported".to_string()));
+            assert!(!names.contains(&"hidden".to_string()));
+
+            let importer = main.new_bare_submodule(frame, "Importer")?.unwrap();
+            importer.import(frame, source, &["hidden"])?.unwrap();
+            assert!(importer.global("hidden").is_ok());
+            assert!(importer.global("exported").is_err());
+
+            let user = main.new_bare_submodule(frame, "User")?.unwrap();
+            user.using(frame, source)?.unwrap();
+            assert!(user.global("exported").is_ok());
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn module_reflection() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(12, |global, frame| {
+            let main = Module::main(global);
+            let module = main.new_submodule(frame, "Reflection")?.unwrap();
+            module
+                .eval_string(
+                    frame,
+                    "export model\nmodel() = 1\nhelper() = 2\nvariable = 3\nmodule Inner end",
+                )?
+                .unwrap();
+
+            let exported: Vec<String> = module
+                .names(frame, false, false)?
+                .into_iter()
+                .map(|n| n.as_string())
+                .collect();
+            assert!(exported.contains(&"model".to_string()));
+            assert!(!exported.contains(&"helper".to_string()));
+
+            let all: Vec<String> = module
+                .names(frame, true, false)?
+                .into_iter()
+                .map(|n| n.as_string())
+                .collect();
+            assert!(all.contains(&"helper".to_string()));
+
+            let submodules = module.submodules(frame)?;
+            assert_eq!(submodules.len(), 1);
+            assert_eq!(submodules[0].name().as_string(), "Inner");
+
+            assert!(module.is_defined("helper"));
+            assert!(!module.is_defined("nonexistent"));
+            assert!(module.is_const("model"));
+            assert!(!module.is_const("variable"));
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn module_binding() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(12, |global, frame| {
+            let main = Module::main(global);
+            let module = main.new_submodule(frame, "BindingTest")?.unwrap();
+            module
+                .eval_string(frame, "variable = 3\nconst CONSTANT = 4")?
+                .unwrap();
+
+            let variable = module.binding(frame, "variable")?;
+            assert_eq!(variable.name().as_string(), "variable");
+            assert_eq!(variable.owner().name().as_string(), "BindingTest");
+            assert_eq!(variable.value().cast::<i64>()?, 3);
+            assert!(!variable.is_const());
+            assert!(!variable.is_exported());
+
+            let constant = module.binding(frame, "CONSTANT")?;
+            assert!(constant.is_const());
+
+            let println = module.binding(frame, "println")?;
+            assert_eq!(println.owner().name().as_string(), "Base");
+            assert!(println.is_exported());
+
+            assert!(module.binding(frame, "nonexistent").is_err());
+
+            // The value is rooted, reassigning the global doesn't free it.
+            module.eval_string(frame, "array = [1, 2, 3]")?.unwrap();
+            let array = module.binding(frame, "array")?;
+            module.eval_string(frame, "array = nothing")?.unwrap();
+            unsafe {
+                frame.gc_collect(GcCollection::Full);
+            }
+            assert_eq!(array.value().cast::<Array>()?.dimensions().size(), 3);
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn set_global_checked() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(8, |global, frame| {
+            let main = Module::main(global);
+            let module = main.new_submodule(frame, "SetGlobalChecked")?.unwrap();
+            module
+                .eval_string(frame, "const CONSTANT = 1\nvariable = 2")?
+                .unwrap();
+
+            let value = Value::new(frame, 3i64)?;
+            let previous = module.set_global_checked(frame, "variable", value)?;
+            assert_eq!(previous.unwrap().cast::<i64>()?, 2);
+            assert_eq!(module.global("variable")?.cast::<i64>()?, 3);
+
+            assert!(module
+                .set_global_checked(frame, "new_variable", value)?
+                .is_none());
+            assert_eq!(module.global("new_variable")?.cast::<i64>()?, 3);
+
+            assert!(module.set_global_checked(frame, "CONSTANT", value).is_err());
+            assert_eq!(module.global("CONSTANT")?.cast::<i64>()?, 1);
+
+            // `println` has been imported from `Base`.
+            module.eval_string(frame, "println")?.unwrap();
+            assert!(module.set_global_checked(frame, "println", value).is_err());
+            Ok(())
+        })
+        .unwrap();
+    })
+}
+
+#[test]
+fn remove_global() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        jlrs.frame(8, |global, frame| {
+            let main = Module::main(global);
+            let module = main.new_submodule(frame, "RemoveGlobal")?.unwrap();
+            module
+                .eval_string(frame, "const CONSTANT = 1\nparameters = [1.0, 2.0]")?
+                .unwrap();
+
+            let removed = module.remove_global(frame, "parameters")?;
+            assert!(removed.is::<Array>());
+            assert!(!module.is_defined("parameters"));
+            assert!(module.eval_string(frame, "parameters")?.is_err());
+
+            let value = Value::new(frame, 3.0f64)?;
+            module.set_global_checked(frame, "parameters", value)?;
+            assert_eq!(module.global("parameters")?.cast::<f64>()?, 3.0);
+
+            assert!(module.remove_global(frame, "CONSTANT").is_err());
+            assert!(module.remove_global(frame, "nonexistent").is_err());
+            Ok(())
+        })
+        .unwrap();
+    })
+}
@@ -0,0 +1,76 @@
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+use jlrs::value::simple_vector::SimpleVector;
+use jlrs::value::union::Union;
+use jlrs::value::union_all::UnionAll;
+
+#[test]
+fn return_multiple_values_from_nested_frame() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(3, |_global, frame| {
+            let [out1, out2, out3] = frame.outputs()?;
+            let (a, b, c) = frame.frame(2, |frame| {
+                let a = Value::new(frame, 1u8)?;
+                let b = Value::new(frame, 2u16)?;
+                let c = Value::new_output(frame, out3, 3u32);
+                Ok((a.extend(frame, out1), b.extend(frame, out2), c))
+            })?;
+
+            assert_eq!(frame.size(), 3);
+            assert_eq!(a.cast::<u8>()?, 1);
+            assert_eq!(b.cast::<u16>()?, 2);
+            assert_eq!(c.cast::<u32>()?, 3);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn outputs_overflow() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(1, |_global, frame| {
+            assert!(frame.outputs::<2>().is_err());
+            assert_eq!(frame.size(), 0);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn type_outputs() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(3, |global, frame| {
+            let [out1, out2, out3] = frame.outputs()?;
+            let (un, ty, svec) = frame.frame(0, |frame| {
+                let mut types = [
+                    DataType::bool_type(global).as_value(),
+                    DataType::int32_type(global).as_value(),
+                ];
+                let un = Value::new_union_output(frame, out1, &mut types)?;
+
+                let ty = UnionAll::ref_type(global).as_value().apply_type_output(
+                    frame,
+                    out2,
+                    &mut [DataType::float64_type(global).as_value()],
+                );
+
+                let svec = SimpleVector::with_capacity_output(frame, out3, 2);
+                Ok((un, ty, svec))
+            })?;
+
+            assert!(un.is::<Union>());
+            assert!(ty.is::<DataType>());
+            assert_eq!(svec.len(), 2);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
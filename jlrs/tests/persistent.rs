@@ -0,0 +1,106 @@
+use jlrs::persistent::PersistentValue;
+use jlrs::prelude::*;
+use jlrs::traits::gc::{Gc, GcCollection};
+use jlrs::util::JULIA;
+
+fn n_persistent(jlrs: &mut Julia) -> usize {
+    jlrs.frame(1, |global, frame| {
+        PersistentValue::release_dropped(frame)?;
+        Module::main(global)
+            .submodule("Jlrs")?
+            .function("npersistent")?
+            .call0(frame)?
+            .unwrap()
+            .cast::<i64>()
+            .map(|n| n as usize)
+    })
+    .unwrap()
+}
+
+#[test]
+fn persistent_value_outlives_frame() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let n = n_persistent(&mut jlrs);
+
+        let persistent = jlrs
+            .frame(1, |_global, frame| {
+                let value = Value::new(frame, 3.0f64)?;
+                PersistentValue::new(frame, value)
+            })
+            .unwrap();
+
+        assert_eq!(n_persistent(&mut jlrs), n + 1);
+
+        jlrs.frame(2, |global, frame| {
+            let rooted = persistent.root(frame)?;
+            let out = Module::base(global)
+                .function("sqrt")?
+                .call1(frame, persistent.value())?
+                .unwrap();
+
+            assert_eq!(rooted.cast::<f64>()?, 3.0);
+            assert_eq!(persistent.cast::<f64>()?, 3.0);
+            assert_eq!(out.cast::<f64>()?, 3.0f64.sqrt());
+            Ok(())
+        })
+        .unwrap();
+
+        drop(persistent);
+        assert_eq!(n_persistent(&mut jlrs), n);
+    });
+}
+
+#[test]
+fn persistent_value_survives_gc() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let persistent = jlrs
+            .frame(2, |_global, frame| {
+                let value = Value::eval_string(frame, "[1, 2, 3]")?.unwrap();
+                PersistentValue::new(frame, value)
+            })
+            .unwrap();
+
+        unsafe {
+            jlrs.gc_collect(GcCollection::Full);
+        }
+
+        jlrs.frame(1, |global, frame| {
+            let sum = Module::base(global)
+                .function("sum")?
+                .call1(frame, persistent.value())?
+                .unwrap();
+
+            assert_eq!(sum.cast::<i64>()?, 6);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn persistent_value_on_foreign_thread() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let value = jlrs
+            .frame(2, |_global, frame| {
+                let value = Value::new(frame, 1usize)?;
+                PersistentValue::new(frame, value)
+            })
+            .unwrap();
+
+        // The value can be sent to and dropped on another thread, but not used there.
+        let res = std::thread::spawn(move || {
+            let res = std::panic::catch_unwind(|| value.value().is::<usize>());
+            drop(value);
+            res
+        })
+        .join()
+        .unwrap();
+
+        assert!(res.is_err());
+    });
+}
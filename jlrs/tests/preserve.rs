@@ -0,0 +1,95 @@
+use jlrs::persistent::{GcPreserve, PersistentValue};
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+
+#[test]
+fn fill_preserved_array_on_other_thread() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let sum = jlrs
+            .frame(3, |global, frame| {
+                let array = Value::new_array::<f64, _, _>(frame, 100)?;
+                let mut preserved = array.preserve(frame)?;
+                let mut data = unsafe { preserved.inline_data_mut::<f64>(0)? };
+
+                std::thread::scope(|scope| {
+                    scope.spawn(|| {
+                        for (i, x) in data.iter_mut().enumerate() {
+                            *x = i as f64;
+                        }
+                    });
+
+                    Value::new(frame, 1usize).map(|_| ())
+                })?;
+
+                Module::base(global)
+                    .function("sum")?
+                    .call1(frame, array)?
+                    .unwrap()
+                    .cast::<f64>()
+            })
+            .unwrap();
+
+        assert_eq!(sum, 4950.0);
+    });
+}
+
+#[test]
+fn read_preserved_arrays() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |_global, frame| {
+            let a = Value::new_array::<f32, _, _>(frame, (2, 2))?;
+            let b = Value::new(frame, 1usize)?;
+            let preserved = GcPreserve::new(frame, &[a, b])?;
+            assert_eq!(preserved.len(), 2);
+            assert!(preserved.value(2).is_none());
+
+            unsafe {
+                let data = preserved.inline_data::<f32>(0)?;
+                assert_eq!(data.dimensions().as_slice(), &[2, 2]);
+                assert_eq!(data.iter().count(), 4);
+
+                assert!(preserved.inline_data::<f64>(0).is_err());
+                assert!(preserved.inline_data::<usize>(1).is_err());
+                assert!(preserved.inline_data::<f32>(2).is_err());
+            }
+
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn preserved_values_released_on_drop() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(4, |global, frame| {
+            let npersistent = Module::main(global)
+                .submodule("Jlrs")?
+                .function("npersistent")?;
+            PersistentValue::release_dropped(frame)?;
+            let n = npersistent.call0(frame)?.unwrap().cast::<i64>()?;
+
+            let a = Value::new(frame, 1usize)?;
+            let b = Value::new(frame, 2usize)?;
+            let preserved = GcPreserve::new(frame, &[a, b])?;
+
+            frame.frame(1, |frame| {
+                let m = npersistent.call0(frame)?.unwrap().cast::<i64>()?;
+                assert_eq!(m, n + 1);
+                Ok(())
+            })?;
+
+            std::mem::drop(preserved);
+            let m = npersistent.call0(frame)?.unwrap().cast::<i64>()?;
+            assert_eq!(m, n);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
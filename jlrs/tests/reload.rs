@@ -0,0 +1,146 @@
+use jlrs::prelude::*;
+use jlrs::reload::Reloader;
+use jlrs::util::JULIA;
+use std::fs;
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+fn write_file(name: &str, code: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(name);
+    fs::write(&path, code).unwrap();
+    path
+}
+
+#[test]
+fn track_and_reload() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let mut reloader = Reloader::new();
+        let path = write_file(
+            "jlrs_reload_test.jl",
+            "add(a, b) = a + b\nsub(a, b) = a - b\n",
+        );
+
+        jlrs.frame(0, |_global, frame| {
+            reloader.track(frame, &path, "ReloadTest")
+        })
+        .unwrap();
+        assert!(reloader.modified_files().is_empty());
+        assert_eq!(reloader.tracked_files().len(), 1);
+
+        fs::write(
+            &path,
+            "add(a, b) = a + b\nsub(a, b) = b - a\nmul(a, b) = a * b\n",
+        )
+        .unwrap();
+
+        jlrs.frame(4, |global, frame| {
+            let report = reloader.reload_file(frame, &path)?;
+            assert_eq!(report.changed, vec!["mul".to_string(), "sub".to_string()]);
+
+            let a = Value::new(frame, 3isize)?;
+            let b = Value::new(frame, 2isize)?;
+            let out = reloader
+                .module(global, frame, &path)?
+                .function("sub")?
+                .call2(frame, a, b)?
+                .unwrap()
+                .cast::<isize>()?;
+            assert_eq!(out, -1);
+
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn reload_error_keeps_module() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let mut reloader = Reloader::new();
+        let path = write_file("jlrs_reload_error_test.jl", "value() = 1\n");
+
+        jlrs.frame(0, |_global, frame| {
+            reloader.track(frame, &path, "ReloadError")
+        })
+        .unwrap();
+
+        fs::write(&path, "value() = 2\nerror(\"oops\")\n").unwrap();
+
+        jlrs.frame(3, |global, frame| {
+            assert!(reloader.reload_file(frame, &path).is_err());
+
+            let out = reloader
+                .module(global, frame, &path)?
+                .function("value")?
+                .call0(frame)?
+                .unwrap()
+                .cast::<i64>()?;
+            assert_eq!(out, 1);
+
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn reload_continues_after_error() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let mut reloader = Reloader::new();
+        let failing = write_file("jlrs_reload_failing_test.jl", "value() = 1\n");
+        let working = write_file("jlrs_reload_working_test.jl", "value() = 1\n");
+
+        jlrs.frame(0, |_global, frame| {
+            reloader.track(frame, &failing, "ReloadFailing")?;
+            reloader.track(frame, &working, "ReloadWorking")
+        })
+        .unwrap();
+
+        // Make sure the modification times change.
+        sleep(Duration::from_millis(1100));
+        fs::write(&failing, "value() = 2\nerror(\"oops\")\n").unwrap();
+        fs::write(&working, "value() = 2\nother() = 3\n").unwrap();
+
+        jlrs.frame(0, |_global, frame| {
+            let reports = reloader.reload(frame);
+            assert_eq!(reports.len(), 2);
+            assert!(reports[0].is_err());
+
+            let report = reports[1].as_ref().unwrap();
+            assert_eq!(report.path, working.canonicalize().unwrap());
+            assert_eq!(
+                report.changed,
+                vec!["other".to_string(), "value".to_string()]
+            );
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            reloader.modified_files(),
+            vec![failing.canonicalize().unwrap()]
+        );
+    });
+}
+
+#[test]
+fn untracked_file() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let mut reloader = Reloader::new();
+        let path = write_file("jlrs_reload_untracked_test.jl", "");
+
+        jlrs.frame(0, |_global, frame| {
+            assert!(reloader.reload_file(frame, &path).is_err());
+            assert!(reloader
+                .track(frame, "jlrs_reload_does_not_exist.jl", "Missing")
+                .is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
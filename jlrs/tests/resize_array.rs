@@ -0,0 +1,194 @@
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+
+#[test]
+fn push_and_extend_typed() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let data = jlrs
+            .frame(1, |_, frame| {
+                let arr = Value::new_array::<f64, _, _>(frame, 0)?
+                    .cast::<Array>()?
+                    .into_typed_array::<f64>()?;
+
+                arr.push(frame, 1.0)?;
+                arr.extend_from_slice(frame, &[2.0, 3.0])?;
+                arr.insert(frame, 0, 0.0)?;
+                arr.copy_inline_data()
+            })
+            .unwrap();
+
+        let (data, dims) = data.splat();
+        assert_eq!(dims.as_slice(), &[4]);
+        assert_eq!(data, vec![0.0, 1.0, 2.0, 3.0]);
+    });
+}
+
+#[test]
+fn delete_resize_truncate_typed() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let data = jlrs
+            .frame(1, |_, frame| {
+                let arr = Value::new_array::<i32, _, _>(frame, 0)?
+                    .cast::<Array>()?
+                    .into_typed_array::<i32>()?;
+
+                arr.sizehint(frame, 8)?;
+                arr.extend_from_slice(frame, &[1, 2, 3, 4])?;
+                arr.delete_at(frame, 1)?;
+                arr.resize(frame, 5, 7)?;
+                arr.truncate(frame, 4)?;
+                arr.truncate(frame, 10)?;
+                arr.copy_inline_data()
+            })
+            .unwrap();
+
+        let (data, _) = data.splat();
+        assert_eq!(data, vec![1, 3, 4, 7]);
+    });
+}
+
+#[test]
+fn push_values() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(4, |global, frame| {
+            let elty = DataType::any_type(global).as_value();
+            let arr = Value::new_array_of_type(frame, elty, 0)?.cast::<Array>()?;
+            let a = Value::new(frame, 1u8)?;
+            let b = Value::new(frame, "foo")?;
+
+            arr.push(frame, a)?;
+            arr.extend_from_slice(frame, &[b, a])?;
+            arr.insert(frame, 1, b)?;
+            arr.delete_at(frame, 0)?;
+
+            assert_eq!(arr.dimensions().as_slice(), &[3]);
+            let data = unsafe { arr.value_data(frame)? };
+            assert!(data[0].egal(b));
+            assert!(data[1].egal(b));
+            assert!(data[2].egal(a));
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn push_wrong_type() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |_, frame| {
+            let arr = Value::new_array::<f64, _, _>(frame, 0)?.cast::<Array>()?;
+            let value = Value::new(frame, 1u8)?;
+            assert!(arr.push(frame, value).is_err());
+            assert_eq!(arr.dimensions().as_slice(), &[0]);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn out_of_bounds() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(1, |_, frame| {
+            let arr = Value::new_array::<f64, _, _>(frame, 2)?
+                .cast::<Array>()?
+                .into_typed_array::<f64>()?;
+
+            assert!(arr.insert(frame, 3, 1.0).is_err());
+            assert!(arr.delete_at(frame, 2).is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn cannot_resize_matrix() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(1, |_, frame| {
+            let arr = Value::new_array::<f64, _, _>(frame, (2, 2))?
+                .cast::<Array>()?
+                .into_typed_array::<f64>()?;
+
+            assert!(arr.push(frame, 1.0).is_err());
+            assert!(arr.truncate(frame, 0).is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn cannot_resize_borrowed() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+        let mut data = vec![1u64, 2, 3];
+
+        jlrs.frame(1, |_, frame| {
+            let arr = Value::borrow_array(frame, &mut data, 3)?
+                .cast::<Array>()?
+                .into_typed_array::<u64>()?;
+
+            match *arr.push(frame, 4).unwrap_err() {
+                JlrsError::BorrowedArray => (),
+                _ => panic!("Expected JlrsError::BorrowedArray"),
+            }
+            assert!(arr.delete_at(frame, 0).is_err());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(data, vec![1, 2, 3]);
+    });
+}
+
+#[test]
+fn cannot_resize_shared() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |_, frame| {
+            let arr = Value::eval_string(frame, "a = [1, 2, 3, 4]; b = reshape(a, 2, 2); a")?
+                .unwrap()
+                .cast::<Array>()?;
+
+            let value = Value::new(frame, 5i64)?;
+            match *arr.push(frame, value).unwrap_err() {
+                JlrsError::SharedArray => (),
+                _ => panic!("Expected JlrsError::SharedArray"),
+            }
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn shrink_ignores_element_type() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |_, frame| {
+            let arr = Value::new_array::<f64, _, _>(frame, 4)?.cast::<Array>()?;
+            let value = Value::new(frame, 1u8)?;
+
+            arr.resize(frame, 2, value)?;
+            assert_eq!(arr.dimensions().as_slice(), &[2]);
+            assert!(arr.resize(frame, 3, value).is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
@@ -26,6 +26,23 @@ fn cannot_init_again() {
     });
 }
 
+#[test]
+fn cannot_init_from_other_thread() {
+    JULIA.with(|_j| {
+        let res = std::thread::spawn(|| unsafe { Julia::init(42).map(|_| ()) })
+            .join()
+            .unwrap();
+
+        match res {
+            Err(e) => match *e {
+                JlrsError::ForeignThread(_) => (),
+                _ => panic!("Unexpected error"),
+            },
+            Ok(_) => panic!("Julia was initialized again"),
+        }
+    });
+}
+
 #[test]
 fn include_error() {
     JULIA.with(|j| {
@@ -0,0 +1,80 @@
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+
+#[test]
+fn static_scope_reuses_slots() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let sum = jlrs
+            .frame(3, |global, frame| {
+                let func = Module::base(global).function("sqrt")?;
+                let four = Value::new(frame, 4.0f64)?;
+                let mut sum = 0.0;
+
+                for _ in 0..100 {
+                    sum += frame.scope(|frame| {
+                        let x = Value::new(frame, 9.0f64)?;
+                        let y = func.call1(frame, x)?.unwrap();
+                        y.cast::<f64>()
+                    })?;
+                }
+
+                assert_eq!(frame.size(), 1);
+                assert_eq!(four.cast::<f64>()?, 4.0);
+                Ok(sum)
+            })
+            .unwrap();
+
+        assert_eq!(sum, 300.0);
+    });
+}
+
+#[test]
+fn static_scope_overflow() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(1, |_global, frame| {
+            let res = frame.scope(|frame| {
+                Value::new(frame, 1usize)?;
+                Value::new(frame, 2usize)?;
+                Ok(())
+            });
+
+            assert!(res.is_err());
+            assert_eq!(frame.size(), 0);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn dynamic_scope_reuses_slots() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        let sum = jlrs
+            .dynamic_frame(|global, frame| {
+                let func = Module::base(global).function("sqrt")?;
+                let four = Value::new(frame, 4.0f64)?;
+                let mut sum = 0.0;
+
+                for _ in 0..100_000 {
+                    sum += frame.scope(|frame| {
+                        let x = Value::new(frame, 9.0f64)?;
+                        let y = func.call1(frame, x)?.unwrap();
+                        y.cast::<f64>()
+                    })?;
+                }
+
+                assert_eq!(frame.size(), 1);
+                assert_eq!(four.cast::<f64>()?, 4.0);
+                Ok(sum)
+            })
+            .unwrap();
+
+        assert_eq!(sum, 300_000.0);
+    });
+}
@@ -0,0 +1,102 @@
+use jlrs::prelude::*;
+use jlrs::util::JULIA;
+use jlrs::value::simple_vector::SimpleVector;
+
+#[test]
+fn traverse_without_slots() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |_global, frame| {
+            let value = Value::eval_string(frame, "Ref(Ref([1, 2, 3]))")?.unwrap();
+            let data = value.get_field_ref("x")?.get_nth_field_ref(0)?;
+            assert_eq!(frame.size(), 1);
+            assert!(!data.is_undefined());
+            assert!(data.is::<Array>());
+
+            let data = unsafe { data.root(frame)? }.cast::<Array>()?;
+            assert_eq!(frame.size(), 2);
+            assert_eq!(data.copy_inline_data::<i64>()?.as_slice(), &[1, 2, 3]);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn inline_field_ref() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(1, |_global, frame| {
+            let value = Value::eval_string(frame, "(1, 2)")?.unwrap();
+            assert!(value.get_nth_field_ref(0).is_err());
+            assert!(value.get_nth_field_ref(2).is_err());
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn array_value_refs() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(2, |_global, frame| {
+            let value = Value::eval_string(frame, "a = Vector{Any}(undef, 2); a[2] = 3.0; a")?
+                .unwrap()
+                .cast::<Array>()?;
+
+            let (undef, elem) = {
+                let refs = value.value_refs(frame)?;
+                (refs[0], refs[1])
+            };
+
+            assert!(undef.is_undefined());
+            assert!(unsafe { undef.root(frame) }.is_err());
+            assert!(elem.is::<f64>());
+
+            let out = frame.output()?;
+            let elem = unsafe { elem.root_output(frame, out)? };
+            assert_eq!(elem.cast::<f64>()?, 3.0);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn svec_data_refs() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(1, |_global, frame| {
+            let svec = SimpleVector::with_capacity(frame, 2)?;
+            let refs = svec.data_refs();
+            assert_eq!(refs.len(), 2);
+            assert!(refs.iter().all(|r| r.is_undefined()));
+            Ok(())
+        })
+        .unwrap();
+    });
+}
+
+#[test]
+fn module_global_ref() {
+    JULIA.with(|j| {
+        let mut jlrs = j.borrow_mut();
+
+        jlrs.frame(3, |global, frame| {
+            let func = Module::base(global).global_ref("sqrt")?;
+            assert!(Module::base(global).global_ref("no_such_global").is_err());
+
+            let func = unsafe { func.root(frame)? };
+            let x = Value::new(frame, 4.0f64)?;
+            let res = func.call1(frame, x)?.unwrap();
+            assert_eq!(res.cast::<f64>()?, 2.0);
+            Ok(())
+        })
+        .unwrap();
+    });
+}
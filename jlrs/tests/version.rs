@@ -0,0 +1,10 @@
+use jlrs::util::JULIA;
+
+#[test]
+fn runtime_version_matches() {
+    JULIA.with(|_| {
+        let version = jlrs::version().unwrap();
+        let expected = format!("{}.{}", version.major, version.minor);
+        assert_eq!(expected, jlrs::JULIA_VERSION);
+    });
+}
@@ -38,6 +38,98 @@ mod example {
         }
     }
 
+    struct NestedTask {
+        dims: isize,
+        iters: isize,
+        n: usize,
+        sender: Sender<JlrsResult<f64>>,
+    }
+
+    #[async_trait(?Send)]
+    impl JuliaTask for NestedTask {
+        type T = f64;
+        type R = Sender<JlrsResult<Self::T>>;
+
+        async fn run<'base>(
+            &mut self,
+            global: Global<'base>,
+            frame: &mut AsyncFrame<'base>,
+        ) -> JlrsResult<Self::T> {
+            let func = Module::main(global)
+                .submodule("MyModule")?
+                .function("complexfunc")?;
+            let dims = Value::new(frame, self.dims)?;
+            let mut sum = 0.0;
+
+            for iters in 0..self.n as isize {
+                let iters = self.iters + iters;
+                sum += frame
+                    .async_frame(|frame| {
+                        Box::pin(async move {
+                            let iters = Value::new(frame, iters)?;
+                            func.call_async(frame, &mut [dims, iters])
+                                .await?
+                                .unwrap()
+                                .cast::<f64>()
+                        })
+                    })
+                    .await?;
+            }
+
+            assert_eq!(frame.size(), 1);
+            Ok(sum)
+        }
+
+        fn return_channel(&self) -> Option<&Sender<JlrsResult<Self::T>>> {
+            Some(&self.sender)
+        }
+    }
+
+    struct OutputTask {
+        dims: isize,
+        iters: isize,
+        sender: Sender<JlrsResult<f64>>,
+    }
+
+    #[async_trait(?Send)]
+    impl JuliaTask for OutputTask {
+        type T = f64;
+        type R = Sender<JlrsResult<Self::T>>;
+
+        async fn run<'base>(
+            &mut self,
+            global: Global<'base>,
+            frame: &mut AsyncFrame<'base>,
+        ) -> JlrsResult<Self::T> {
+            let func = Module::main(global)
+                .submodule("MyModule")?
+                .function("complexfunc")?;
+            let output = frame.output()?;
+            let (dims, iters) = (self.dims, self.iters);
+
+            let v = frame
+                .async_frame(|frame| {
+                    Box::pin(async move {
+                        let dims = Value::new(frame, dims)?;
+                        let iters = Value::new(frame, iters)?;
+                        func.with_output(output)
+                            .call_async(frame, &mut [dims, iters])
+                            .await
+                    })
+                })
+                .await?
+                .unwrap();
+
+            // Only the output is still in use after the nested frame has been dropped.
+            assert_eq!(frame.size(), 1);
+            v.cast::<f64>()
+        }
+
+        fn return_channel(&self) -> Option<&Sender<JlrsResult<Self::T>>> {
+            Some(&self.sender)
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -71,6 +163,31 @@ mod example {
             assert_eq!(receiver2.recv().unwrap().unwrap(), 30_000_006.0);
             assert_eq!(receiver1.recv().unwrap().unwrap(), 20_000_004.0);
 
+            let (sender3, receiver3) = crossbeam_channel::bounded(1);
+            julia
+                .try_new_task(NestedTask {
+                    dims: 2,
+                    iters: 1,
+                    n: 32,
+                    sender: sender3,
+                })
+                .unwrap();
+
+            // complexfunc(2, iters) returns 2 * (iters + 1), the task sums this for
+            // iters in 1..=32 with a stack that's too small to hold 32 calls.
+            assert_eq!(receiver3.recv().unwrap().unwrap(), 1120.0);
+
+            let (sender4, receiver4) = crossbeam_channel::bounded(1);
+            julia
+                .try_new_task(OutputTask {
+                    dims: 4,
+                    iters: 5,
+                    sender: sender4,
+                })
+                .unwrap();
+
+            assert_eq!(receiver4.recv().unwrap().unwrap(), 24.0);
+
             std::mem::drop(julia);
             handle
                 .join()
@@ -0,0 +1,547 @@
+//! Borrow data from Julia arrays as `nalgebra`'s `DMatrixSlice` and `DVectorSlice`, and move
+//! `DMatrix`s and static matrices between Rust and Julia.
+//!
+//! This crate defines a trait, `NalgebraSlice`, that provides methods that return an immutable
+//! or a mutable slice of the array data and is implemented by `Array` and `TypedArray` from jlrs.
+//! Julia arrays are stored in column-major order just like `nalgebra`'s matrices, so no data has
+//! to be copied. Only arrays with one or two dimensions can be borrowed, a one-dimensional array
+//! is treated as a matrix with a single column.
+//!
+//! An owned `DMatrix` can be moved to Julia with [`move_dmatrix`], its backing storage is reused
+//! as the data of the new Julia array. Finally, the module [`static_array`] provides
+//! [`StaticMatrix`] and [`StaticVector`], which wrap `SMatrix` and `SVector` and have the same
+//! layout as `SMatrix` and `SVector` from `StaticArrays.jl`.
+//!
+//! [`move_dmatrix`]: fn.move_dmatrix.html
+//! [`static_array`]: static_array/index.html
+//! [`StaticMatrix`]: static_array/struct.StaticMatrix.html
+//! [`StaticVector`]: static_array/struct.StaticVector.html
+
+pub mod static_array;
+
+pub use static_array::{StaticMatrix, StaticVector};
+
+use jlrs::error::other;
+use jlrs::frame::Output;
+use jlrs::prelude::*;
+use jlrs::traits::{IntoJulia, JuliaType};
+use jlrs::value::array::Dimensions;
+use nalgebra::{DMatrix, DMatrixSlice, DMatrixSliceMut, DVectorSlice, DVectorSliceMut, Scalar};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+mod private {
+    use jlrs::traits::ValidLayout;
+    use jlrs::value::array::{Array, TypedArray};
+
+    pub trait Sealed {}
+    impl<'frame, 'data> Sealed for Array<'frame, 'data> {}
+    impl<'frame, 'data, T> Sealed for TypedArray<'frame, 'data, T> where T: Copy + ValidLayout {}
+}
+
+/// Errors that can occur when a Julia array is borrowed as a matrix or vector slice.
+#[derive(Debug)]
+pub enum NalgebraError {
+    /// The array has more than two dimensions, or none at all.
+    NotAMatrix(usize),
+    /// The array doesn't have exactly one dimension.
+    NotAVector(usize),
+    /// `StaticArrays.SArray` can't be found because the `StaticArrays` package hasn't been
+    /// loaded.
+    StaticArraysNotLoaded,
+}
+
+impl Display for NalgebraError {
+    fn fmt(&self, formatter: &mut Formatter) -> FmtResult {
+        match self {
+            NalgebraError::NotAMatrix(n) => write!(
+                formatter,
+                "An array with {} dimensions cannot be borrowed as a matrix",
+                n
+            ),
+            NalgebraError::NotAVector(n) => write!(
+                formatter,
+                "An array with {} dimensions cannot be borrowed as a vector",
+                n
+            ),
+            NalgebraError::StaticArraysNotLoaded => {
+                write!(formatter, "The StaticArrays package has not been loaded")
+            }
+        }
+    }
+}
+
+impl Error for NalgebraError {}
+
+/// Trait to borrow Julia arrays with inline data as `nalgebra`'s `DMatrixSlice`,
+/// `DMatrixSliceMut`, `DVectorSlice` and `DVectorSliceMut`.
+pub trait NalgebraSlice<'borrow, T>: private::Sealed {
+    /// Borrow the data in the array as a `DMatrixSlice`. Returns an error if the wrong type is
+    /// provided, the data is not stored inline, or the array doesn't have one or two dimensions.
+    fn matrix_slice<'frame: 'borrow, F>(
+        self,
+        frame: &'borrow F,
+    ) -> JlrsResult<DMatrixSlice<'borrow, T>>
+    where
+        F: Frame<'frame>,
+        T: ValidLayout + Copy + Scalar;
+
+    /// Mutably borrow the data in the array as a `DMatrixSliceMut`. Returns an error if the wrong
+    /// type is provided, the data is not stored inline, or the array doesn't have one or two
+    /// dimensions.
+    fn matrix_slice_mut<'frame: 'borrow, F>(
+        self,
+        frame: &'borrow mut F,
+    ) -> JlrsResult<DMatrixSliceMut<'borrow, T>>
+    where
+        F: Frame<'frame>,
+        T: ValidLayout + Copy + Scalar;
+
+    /// Borrow the data in the array as a `DVectorSlice`. Returns an error if the wrong type is
+    /// provided, the data is not stored inline, or the array isn't one-dimensional.
+    fn vector_slice<'frame: 'borrow, F>(
+        self,
+        frame: &'borrow F,
+    ) -> JlrsResult<DVectorSlice<'borrow, T>>
+    where
+        F: Frame<'frame>,
+        T: ValidLayout + Copy + Scalar;
+
+    /// Mutably borrow the data in the array as a `DVectorSliceMut`. Returns an error if the wrong
+    /// type is provided, the data is not stored inline, or the array isn't one-dimensional.
+    fn vector_slice_mut<'frame: 'borrow, F>(
+        self,
+        frame: &'borrow mut F,
+    ) -> JlrsResult<DVectorSliceMut<'borrow, T>>
+    where
+        F: Frame<'frame>,
+        T: ValidLayout + Copy + Scalar;
+}
+
+impl<'frame: 'borrow, 'data: 'borrow, 'borrow, T: ValidLayout + Copy + Scalar>
+    NalgebraSlice<'borrow, T> for Array<'frame, 'data>
+{
+    fn matrix_slice<'fr: 'borrow, F>(
+        self,
+        frame: &'borrow F,
+    ) -> JlrsResult<DMatrixSlice<'borrow, T>>
+    where
+        F: Frame<'fr>,
+        T: ValidLayout + Copy + Scalar,
+    {
+        let data = self.inline_data::<T, _>(frame)?;
+        let (nrows, ncols) = matrix_shape(data.dimensions())?;
+        Ok(DMatrixSlice::from_slice(data.into_slice(), nrows, ncols))
+    }
+
+    fn matrix_slice_mut<'fr: 'borrow, F>(
+        self,
+        frame: &'borrow mut F,
+    ) -> JlrsResult<DMatrixSliceMut<'borrow, T>>
+    where
+        F: Frame<'fr>,
+        T: ValidLayout + Copy + Scalar,
+    {
+        let data = self.inline_data_mut::<T, _>(frame)?;
+        let (nrows, ncols) = matrix_shape(data.dimensions())?;
+        Ok(DMatrixSliceMut::from_slice(
+            data.into_mut_slice(),
+            nrows,
+            ncols,
+        ))
+    }
+
+    fn vector_slice<'fr: 'borrow, F>(
+        self,
+        frame: &'borrow F,
+    ) -> JlrsResult<DVectorSlice<'borrow, T>>
+    where
+        F: Frame<'fr>,
+        T: ValidLayout + Copy + Scalar,
+    {
+        let data = self.inline_data::<T, _>(frame)?;
+        let len = vector_len(data.dimensions())?;
+        Ok(DVectorSlice::from_slice(data.into_slice(), len))
+    }
+
+    fn vector_slice_mut<'fr: 'borrow, F>(
+        self,
+        frame: &'borrow mut F,
+    ) -> JlrsResult<DVectorSliceMut<'borrow, T>>
+    where
+        F: Frame<'fr>,
+        T: ValidLayout + Copy + Scalar,
+    {
+        let data = self.inline_data_mut::<T, _>(frame)?;
+        let len = vector_len(data.dimensions())?;
+        Ok(DVectorSliceMut::from_slice(data.into_mut_slice(), len))
+    }
+}
+
+impl<'frame: 'borrow, 'data: 'borrow, 'borrow, T: ValidLayout + Copy + Scalar>
+    NalgebraSlice<'borrow, T> for TypedArray<'frame, 'data, T>
+{
+    fn matrix_slice<'fr: 'borrow, F>(
+        self,
+        frame: &'borrow F,
+    ) -> JlrsResult<DMatrixSlice<'borrow, T>>
+    where
+        F: Frame<'fr>,
+        T: ValidLayout + Copy + Scalar,
+    {
+        let data = self.inline_data(frame)?;
+        let (nrows, ncols) = matrix_shape(data.dimensions())?;
+        Ok(DMatrixSlice::from_slice(data.into_slice(), nrows, ncols))
+    }
+
+    fn matrix_slice_mut<'fr: 'borrow, F>(
+        self,
+        frame: &'borrow mut F,
+    ) -> JlrsResult<DMatrixSliceMut<'borrow, T>>
+    where
+        F: Frame<'fr>,
+        T: ValidLayout + Copy + Scalar,
+    {
+        let data = self.inline_data_mut(frame)?;
+        let (nrows, ncols) = matrix_shape(data.dimensions())?;
+        Ok(DMatrixSliceMut::from_slice(
+            data.into_mut_slice(),
+            nrows,
+            ncols,
+        ))
+    }
+
+    fn vector_slice<'fr: 'borrow, F>(
+        self,
+        frame: &'borrow F,
+    ) -> JlrsResult<DVectorSlice<'borrow, T>>
+    where
+        F: Frame<'fr>,
+        T: ValidLayout + Copy + Scalar,
+    {
+        let data = self.inline_data(frame)?;
+        let len = vector_len(data.dimensions())?;
+        Ok(DVectorSlice::from_slice(data.into_slice(), len))
+    }
+
+    fn vector_slice_mut<'fr: 'borrow, F>(
+        self,
+        frame: &'borrow mut F,
+    ) -> JlrsResult<DVectorSliceMut<'borrow, T>>
+    where
+        F: Frame<'fr>,
+        T: ValidLayout + Copy + Scalar,
+    {
+        let data = self.inline_data_mut(frame)?;
+        let len = vector_len(data.dimensions())?;
+        Ok(DVectorSliceMut::from_slice(data.into_mut_slice(), len))
+    }
+}
+
+/// Moves a `DMatrix` from Rust to Julia. Both store their data in column-major order, the
+/// backing storage of the matrix is used as the data of the new two-dimensional array without
+/// copying it.
+///
+/// This function requires one slot on the GC stack and an extra frame is created with a single
+/// slot, temporarily taking 3 additional slots. Returns an error if there are not enough slots
+/// available.
+pub fn move_dmatrix<'frame, T, F>(
+    frame: &mut F,
+    matrix: DMatrix<T>,
+) -> JlrsResult<Value<'frame, 'static>>
+where
+    T: IntoJulia + JuliaType + Scalar,
+    F: Frame<'frame>,
+{
+    let (nrows, ncols) = matrix.shape();
+    let data: Vec<T> = matrix.data.into();
+    Value::move_array(frame, data, (nrows, ncols))
+}
+
+/// Moves a `DMatrix` from Rust to Julia using an `Output`. Both store their data in column-major
+/// order, the backing storage of the matrix is used as the data of the new two-dimensional array
+/// without copying it.
+///
+/// Because an `Output` is used, no additional slot in the current frame is used for the array
+/// itself. An extra frame is created with a single slot, temporarily taking 3 additional slots.
+/// Returns an error if there are not enough slots available.
+pub fn move_dmatrix_output<'output, 'frame, T, F>(
+    frame: &mut F,
+    output: Output<'output>,
+    matrix: DMatrix<T>,
+) -> JlrsResult<Value<'output, 'static>>
+where
+    T: IntoJulia + JuliaType + Scalar,
+    F: Frame<'frame>,
+{
+    let (nrows, ncols) = matrix.shape();
+    let data: Vec<T> = matrix.data.into();
+    Value::move_array_output(frame, output, data, (nrows, ncols))
+}
+
+fn matrix_shape(dims: &Dimensions) -> JlrsResult<(usize, usize)> {
+    match dims.n_dimensions() {
+        1 => Ok((dims.n_elements(0), 1)),
+        2 => Ok((dims.n_elements(0), dims.n_elements(1))),
+        n => other(NalgebraError::NotAMatrix(n)),
+    }
+}
+
+fn vector_len(dims: &Dimensions) -> JlrsResult<usize> {
+    match dims.n_dimensions() {
+        1 => Ok(dims.n_elements(0)),
+        n => other(NalgebraError::NotAVector(n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{move_dmatrix, NalgebraSlice, StaticMatrix, StaticVector};
+    use jlrs::prelude::*;
+    use nalgebra::{DMatrix, DMatrixSlice, DMatrixSliceMut, DVectorSlice, SMatrix, SVector};
+
+    use std::cell::RefCell;
+
+    thread_local! {
+        pub static JULIA: RefCell<Julia> = RefCell::new(unsafe { Julia::init(32).unwrap() });
+    }
+
+    // A stand-in for `StaticArrays.SArray` with the same layout, so these tests don't depend on
+    // the package being installed.
+    const STATIC_ARRAYS: &str = "module StaticArrays
+        struct SArray{S<:Tuple, T, N, L}
+            data::NTuple{L, T}
+        end
+    end";
+
+    fn load_static_arrays<'frame, F, V>(global: Global, frame: &mut F, value: V) -> JlrsResult<()>
+    where
+        F: Frame<'frame>,
+        V: FnOnce(&mut F) -> JlrsResult<Value<'frame, 'static>>,
+    {
+        if Module::main(global).submodule("StaticArrays").is_err() {
+            assert!(value(frame).is_err());
+            Value::eval_string(frame, STATIC_ARRAYS)?.unwrap();
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn matrix_slice() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|_global, frame| {
+                    let mut data = vec![1usize, 2, 3, 4, 5, 6];
+                    let slice = &mut data.as_mut_slice();
+                    let borrowed = Value::borrow_array(frame, slice, (3, 2))?;
+
+                    let jl_array = borrowed.cast::<Array>()?;
+                    let x = jl_array.inline_data::<usize, _>(frame)?[(1, 0)];
+
+                    let matrix: DMatrixSlice<usize> = jl_array.matrix_slice(frame)?;
+                    assert_eq!(matrix.shape(), (3, 2));
+                    assert_eq!(matrix[(1, 0)], x);
+
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn matrix_slice_wrong_type() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|_global, frame| {
+                    let mut data = vec![1usize, 2, 3, 4, 5, 6];
+                    let slice = &mut data.as_mut_slice();
+                    let borrowed = Value::borrow_array(frame, slice, (3, 2))?;
+
+                    let jl_array = borrowed.cast::<Array>()?;
+                    let matrix: Result<DMatrixSlice<isize>, _> = jl_array.matrix_slice(frame);
+                    assert!(matrix.is_err());
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn matrix_slice_too_many_dimensions() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|_global, frame| {
+                    let mut data = vec![1usize, 2, 3, 4, 5, 6];
+                    let slice = &mut data.as_mut_slice();
+                    let borrowed = Value::borrow_array(frame, slice, (3, 1, 2))?;
+
+                    let jl_array = borrowed.cast::<TypedArray<usize>>()?;
+                    assert!(jl_array.matrix_slice(frame).is_err());
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn matrix_slice_mut() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|_global, frame| {
+                    let mut data = vec![1usize, 2, 3, 4, 5, 6];
+                    let slice = &mut data.as_mut_slice();
+                    let borrowed = Value::borrow_array(frame, slice, (3, 2))?;
+
+                    let jl_array = borrowed.cast::<Array>()?;
+                    let mut inline = jl_array.inline_data_mut::<usize, _>(frame)?;
+                    let x = inline[(1, 0)];
+
+                    inline[(1, 0)] = x + 1;
+
+                    let mut matrix: DMatrixSliceMut<usize> = jl_array.matrix_slice_mut(frame)?;
+                    assert_eq!(matrix[(1, 0)], x + 1);
+                    matrix[(1, 0)] -= 1;
+
+                    let inline = jl_array.inline_data_mut::<usize, _>(frame)?;
+                    assert_eq!(inline[(1, 0)], x);
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn vector_slice() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|_global, frame| {
+                    let mut data = vec![1.0f64, 2.0, 3.0];
+                    let slice = &mut data.as_mut_slice();
+                    let borrowed = Value::borrow_array(frame, slice, 3)?;
+
+                    let jl_array = borrowed.cast::<TypedArray<f64>>()?;
+                    let vector: DVectorSlice<f64> = jl_array.vector_slice(frame)?;
+                    assert_eq!(vector.len(), 3);
+                    assert_eq!(vector[2], 3.0);
+
+                    let matrix = jl_array.matrix_slice(frame)?;
+                    assert_eq!(matrix.shape(), (3, 1));
+
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn vector_slice_two_dimensions() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|_global, frame| {
+                    let mut data = vec![1.0f64, 2.0, 3.0, 4.0];
+                    let slice = &mut data.as_mut_slice();
+                    let borrowed = Value::borrow_array(frame, slice, (2, 2))?;
+
+                    let jl_array = borrowed.cast::<TypedArray<f64>>()?;
+                    assert!(jl_array.vector_slice(frame).is_err());
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn move_matrix() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|_global, frame| {
+                    let matrix = DMatrix::from_row_slice(2, 3, &[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]);
+                    let moved = move_dmatrix(frame, matrix.clone())?;
+
+                    let jl_array = moved.cast::<TypedArray<f32>>()?;
+                    assert_eq!(jl_array.dimensions().as_slice(), &[2, 3]);
+
+                    let data = jl_array.inline_data(frame)?;
+                    assert_eq!(data[(0, 1)], matrix[(0, 1)]);
+                    assert_eq!(data[(1, 2)], matrix[(1, 2)]);
+
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn static_matrix_round_trip() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|global, frame| {
+                    let data = [1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0];
+                    let matrix = StaticMatrix(SMatrix::<f64, 2, 3>::from_row_slice(&data));
+                    load_static_arrays(global, frame, |frame| matrix.into_value(frame))?;
+
+                    let value = matrix.into_value(frame)?;
+                    assert!(value.is::<StaticMatrix<f64, 2, 3>>());
+                    assert!(!value.is::<StaticMatrix<f64, 3, 2>>());
+                    assert!(!value.is::<StaticMatrix<f32, 2, 3>>());
+                    assert_eq!(value.cast::<StaticMatrix<f64, 2, 3>>()?, matrix);
+
+                    // Both are stored in column-major order.
+                    let elem = value
+                        .get_field(frame, "data")?
+                        .get_nth_field(frame, 2)?
+                        .cast::<f64>()?;
+                    assert_eq!(elem, matrix.0[(0, 1)]);
+
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn static_vector_round_trip() {
+        JULIA.with(|j| {
+            let mut julia = j.borrow_mut();
+
+            julia
+                .dynamic_frame(|global, frame| {
+                    let vector = StaticVector(SVector::<i32, 3>::from_column_slice(&[1, 2, 3]));
+                    load_static_arrays(global, frame, |frame| vector.into_value(frame))?;
+
+                    let value = Value::new(frame, vector)?;
+                    assert_eq!(value.cast::<StaticVector<i32, 3>>()?, vector);
+                    assert!(value.cast::<StaticVector<i32, 2>>().is_err());
+                    assert!(value.cast::<StaticMatrix<i32, 3, 1>>().is_err());
+
+                    let value = Value::eval_string(
+                        frame,
+                        "StaticArrays.SArray{Tuple{3}, Int32, 1, 3}((4, 5, 6))",
+                    )?
+                    .unwrap();
+                    let vector = value.cast::<StaticVector<i32, 3>>()?.into_inner();
+                    assert_eq!(vector.as_slice(), &[4, 5, 6]);
+
+                    Ok(())
+                })
+                .unwrap();
+        });
+    }
+}
@@ -0,0 +1,256 @@
+//! Statically sized matrices and vectors that have the same layout as `SMatrix` and `SVector`
+//! from `StaticArrays.jl`.
+//!
+//! Both `nalgebra` and `StaticArrays.jl` store the elements of a statically sized matrix inline
+//! in column-major order. [`StaticMatrix`] and [`StaticVector`] are thin wrappers around
+//! `SMatrix` and `SVector` that implement the traits from jlrs that are required to create them
+//! with [`Value::new`] and to convert a Julia value to them with [`Value::cast`]. The Julia types
+//! are found in `Main.StaticArrays`, so the `StaticArrays` package must be loaded with
+//! `using StaticArrays` before these types can be used.
+//!
+//! The `JuliaType` and `IntoJulia` implementations can't return an error, so [`Value::new`]
+//! panics if `StaticArrays` hasn't been loaded. Use [`StaticMatrix::into_value`] or
+//! [`StaticVector::into_value`] to get an error instead.
+//!
+//! [`StaticMatrix`]: struct.StaticMatrix.html
+//! [`StaticVector`]: struct.StaticVector.html
+//! [`Value::new`]: ../../jlrs/value/struct.Value.html#method.new
+//! [`Value::cast`]: ../../jlrs/value/struct.Value.html#method.cast
+//! [`StaticMatrix::into_value`]: struct.StaticMatrix.html#method.into_value
+//! [`StaticVector::into_value`]: struct.StaticVector.html#method.into_value
+
+use crate::NalgebraError;
+use jlrs::error::other;
+use jlrs::global::Global;
+use jlrs::jl_sys_export::{
+    jl_apply_tuple_type_v, jl_apply_type, jl_datatype_t, jl_gc_enable, jl_new_struct_uninit,
+    jl_value_t,
+};
+use jlrs::prelude::*;
+use jlrs::traits::{Cast, IntoJulia, JuliaType, JuliaTypecheck};
+use jlrs::value::datatype::DataType;
+use jlrs::value::union_all::UnionAll;
+use nalgebra::{SMatrix, SVector, Scalar};
+
+/// A statically sized matrix with `R` rows and `C` columns. It has the same layout as
+/// `StaticArrays.SMatrix{R, C, T}`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct StaticMatrix<T: Scalar + Copy, const R: usize, const C: usize>(pub SMatrix<T, R, C>);
+
+/// A statically sized vector with `N` elements. It has the same layout as
+/// `StaticArrays.SVector{N, T}`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct StaticVector<T: Scalar + Copy, const N: usize>(pub SVector<T, N>);
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> StaticMatrix<T, R, C> {
+    /// Returns the wrapped `SMatrix`.
+    pub fn into_inner(self) -> SMatrix<T, R, C> {
+        self.0
+    }
+
+    /// Create a new `SMatrix{R, C, T}` in Julia, this takes one slot on the GC stack. Unlike
+    /// [`Value::new`], this returns an error if `StaticArrays` hasn't been loaded.
+    ///
+    /// [`Value::new`]: ../../jlrs/value/struct.Value.html#method.new
+    pub fn into_value<'frame, F>(self, frame: &mut F) -> JlrsResult<Value<'frame, 'static>>
+    where
+        T: JuliaType,
+        F: Frame<'frame>,
+    {
+        // Safe because a frame exists, so Julia has been initialized.
+        sarray(unsafe { Global::new() })?;
+        Value::new(frame, self)
+    }
+}
+
+impl<T: Scalar + Copy, const N: usize> StaticVector<T, N> {
+    /// Returns the wrapped `SVector`.
+    pub fn into_inner(self) -> SVector<T, N> {
+        self.0
+    }
+
+    /// Create a new `SVector{N, T}` in Julia, this takes one slot on the GC stack. Unlike
+    /// [`Value::new`], this returns an error if `StaticArrays` hasn't been loaded.
+    ///
+    /// [`Value::new`]: ../../jlrs/value/struct.Value.html#method.new
+    pub fn into_value<'frame, F>(self, frame: &mut F) -> JlrsResult<Value<'frame, 'static>>
+    where
+        T: JuliaType,
+        F: Frame<'frame>,
+    {
+        // Safe because a frame exists, so Julia has been initialized.
+        sarray(unsafe { Global::new() })?;
+        Value::new(frame, self)
+    }
+}
+
+impl<T: Scalar + Copy, const R: usize, const C: usize> From<SMatrix<T, R, C>>
+    for StaticMatrix<T, R, C>
+{
+    fn from(matrix: SMatrix<T, R, C>) -> Self {
+        StaticMatrix(matrix)
+    }
+}
+
+impl<T: Scalar + Copy, const N: usize> From<SVector<T, N>> for StaticVector<T, N> {
+    fn from(vector: SVector<T, N>) -> Self {
+        StaticVector(vector)
+    }
+}
+
+macro_rules! impl_static_array {
+    ($type:ident, [$($dim:ident),+], $($const:ident),+) => {
+        unsafe impl<T, $(const $const: usize),+> ValidLayout for $type<T, $($const),+>
+        where
+            T: Scalar + Copy + ValidLayout,
+        {
+            unsafe fn valid_layout(v: Value) -> bool {
+                is_sarray::<T>(v, &[$($dim),+])
+            }
+        }
+
+        unsafe impl<T, $(const $const: usize),+> JuliaTypecheck for $type<T, $($const),+>
+        where
+            T: Scalar + Copy + ValidLayout,
+        {
+            unsafe fn julia_typecheck(t: DataType) -> bool {
+                <Self as ValidLayout>::valid_layout(t.into())
+            }
+        }
+
+        unsafe impl<T, $(const $const: usize),+> JuliaType for $type<T, $($const),+>
+        where
+            T: Scalar + Copy + JuliaType,
+        {
+            unsafe fn julia_type() -> *mut jl_datatype_t {
+                sarray_type::<T>(&[$($dim),+])
+            }
+        }
+
+        unsafe impl<T, $(const $const: usize),+> IntoJulia for $type<T, $($const),+>
+        where
+            T: Scalar + Copy + JuliaType,
+        {
+            unsafe fn into_julia(&self) -> *mut jl_value_t {
+                let ty = <Self as JuliaType>::julia_type();
+                let container = jl_new_struct_uninit(ty);
+                let data: *mut Self = container.cast();
+                std::ptr::write(data, *self);
+
+                container
+            }
+        }
+
+        unsafe impl<'frame, 'data, T, $(const $const: usize),+> Cast<'frame, 'data>
+            for $type<T, $($const),+>
+        where
+            T: Scalar + Copy + ValidLayout,
+        {
+            type Output = Self;
+
+            fn cast(value: Value<'frame, 'data>) -> JlrsResult<Self::Output> {
+                if value.is_nothing() {
+                    Err(JlrsError::Nothing)?
+                }
+
+                unsafe {
+                    if <Self as ValidLayout>::valid_layout(value.datatype().unwrap().into()) {
+                        return Ok(Self::cast_unchecked(value));
+                    }
+                }
+
+                Err(JlrsError::WrongType)?
+            }
+
+            unsafe fn cast_unchecked(value: Value<'frame, 'data>) -> Self::Output {
+                *(value.ptr().cast::<Self::Output>())
+            }
+        }
+    };
+}
+
+impl_static_array!(StaticMatrix, [R, C], R, C);
+impl_static_array!(StaticVector, [N], N);
+
+// Both `SMatrix{R, C, T}` and `SVector{N, T}` are aliases of `SArray{Tuple{dims...}, T, ndims,
+// length}`.
+unsafe fn is_sarray<T: ValidLayout>(ty: Value, dims: &[usize]) -> bool {
+    let dt = match ty.cast::<DataType>() {
+        Ok(dt) => dt,
+        Err(_) => return false,
+    };
+
+    if dt.name() != "SArray" || dt.type_name().module().name().as_string() != "StaticArrays" {
+        return false;
+    }
+
+    let params = dt.parameters();
+    if params.len() != 4 {
+        return false;
+    }
+
+    match params[0].cast::<DataType>() {
+        Ok(shape) => {
+            let shape = shape.parameters();
+            if shape.len() != dims.len() {
+                return false;
+            }
+
+            for (param, dim) in shape.iter().zip(dims) {
+                match param.cast::<isize>() {
+                    Ok(n) if n as usize == *dim => (),
+                    _ => return false,
+                }
+            }
+        }
+        Err(_) => return false,
+    }
+
+    match params[3].cast::<isize>() {
+        Ok(n) if n as usize == dims.iter().product() => (),
+        _ => return false,
+    }
+
+    T::valid_layout(params[1])
+}
+
+// Returns `StaticArrays.SArray` if the package has been loaded. It's checked to be a `UnionAll`
+// so applying the parameters to it can't throw.
+fn sarray(global: Global) -> JlrsResult<Value> {
+    let sarray = Module::main(global)
+        .submodule("StaticArrays")
+        .and_then(|module| module.global("SArray"));
+
+    match sarray {
+        Ok(sarray) if sarray.is::<UnionAll>() => Ok(sarray),
+        _ => other(NalgebraError::StaticArraysNotLoaded),
+    }
+}
+
+unsafe fn sarray_type<T: JuliaType>(dims: &[usize]) -> *mut jl_datatype_t {
+    let sarray = match sarray(Global::new()) {
+        Ok(sarray) => sarray,
+        Err(e) => panic!("{}", e),
+    };
+
+    // The boxed dimensions and the shape are not rooted, so the GC is disabled until the type
+    // has been created. Concrete types are cached, the result is rooted by that cache.
+    let gc_state = jl_gc_enable(0);
+
+    let mut shape: Vec<*mut jl_value_t> = dims.iter().map(|&d| (d as isize).into_julia()).collect();
+    let shape_ty = jl_apply_tuple_type_v(shape.as_mut_ptr(), shape.len());
+
+    let mut params = [
+        shape_ty.cast(),
+        T::julia_type().cast(),
+        (dims.len() as isize).into_julia(),
+        (dims.iter().product::<usize>() as isize).into_julia(),
+    ];
+
+    let ty = jl_apply_type(sarray.ptr(), params.as_mut_ptr(), params.len());
+    jl_gc_enable(gc_state);
+
+    ty.cast()
+}